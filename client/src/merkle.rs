@@ -0,0 +1,134 @@
+use anchor_lang::prelude::Pubkey;
+use sol_cb::merkle::{allowlist_leaf_hash, hash_pair, leaf_hash};
+
+/// Merkle tree matching the on-chain verifier. Built either over
+/// (kol, amount) payouts for `claim_open_reward`, where leaf `i` is claimed
+/// with `index = i`, or over a KOL allowlist for eligibility gating.
+pub struct MerkleTree {
+    leaves: Vec<(Pubkey, u64)>, // Empty for allowlist trees
+    // levels[0] holds the leaf hashes, the last level holds the root
+    levels: Vec<Vec<[u8; 32]>>,
+}
+
+impl MerkleTree {
+    pub fn new(leaves: Vec<(Pubkey, u64)>) -> Self {
+        let levels = build_levels(
+            leaves
+                .iter()
+                .enumerate()
+                .map(|(index, (kol, amount))| leaf_hash(index as u32, kol, *amount))
+                .collect(),
+        );
+
+        Self { leaves, levels }
+    }
+
+    pub fn allowlist(kols: &[Pubkey]) -> Self {
+        Self {
+            leaves: vec![],
+            levels: build_levels(kols.iter().map(allowlist_leaf_hash).collect()),
+        }
+    }
+
+    pub fn root(&self) -> [u8; 32] {
+        self.levels.last().unwrap()[0]
+    }
+
+    pub fn leaf_count(&self) -> u32 {
+        self.levels[0].len() as u32
+    }
+
+    /// The (kol, amount) payout at `index`; `None` past the end and for
+    /// allowlist trees, which only keep hashes.
+    pub fn leaf(&self, index: u32) -> Option<(Pubkey, u64)> {
+        self.leaves.get(index as usize).copied()
+    }
+
+    /// Sibling hashes from the leaf at `index` up to the root.
+    pub fn proof(&self, index: u32) -> Vec<[u8; 32]> {
+        let mut proof = vec![];
+        let mut position = index as usize;
+
+        for level in &self.levels[..self.levels.len() - 1] {
+            let sibling = position ^ 1;
+            if sibling < level.len() {
+                proof.push(level[sibling]);
+            }
+            position /= 2;
+        }
+
+        proof
+    }
+}
+
+fn build_levels(leaves: Vec<[u8; 32]>) -> Vec<Vec<[u8; 32]>> {
+    assert!(!leaves.is_empty(), "a Merkle tree needs at least one leaf");
+
+    let mut levels = vec![leaves];
+
+    while levels.last().unwrap().len() > 1 {
+        // An unpaired node is carried up to the next level unchanged
+        let next = levels
+            .last()
+            .unwrap()
+            .chunks(2)
+            .map(|pair| match pair {
+                [a, b] => hash_pair(a, b),
+                [a] => *a,
+                _ => unreachable!(),
+            })
+            .collect();
+        levels.push(next);
+    }
+
+    levels
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sol_cb::merkle::verify_proof;
+
+    #[test]
+    fn proofs_verify_against_root() {
+        for size in 1..=9u64 {
+            let leaves = (0..size)
+                .map(|i| (Pubkey::new_unique(), 1_000 * (i + 1)))
+                .collect::<Vec<_>>();
+            let tree = MerkleTree::new(leaves);
+
+            for index in 0..tree.leaf_count() {
+                let (kol, amount) = tree.leaf(index).unwrap();
+                let leaf = leaf_hash(index, &kol, amount);
+                assert!(verify_proof(&tree.proof(index), &tree.root(), leaf));
+            }
+        }
+    }
+
+    #[test]
+    fn proof_rejects_wrong_amount() {
+        let leaves = (0..4)
+            .map(|_| (Pubkey::new_unique(), 500))
+            .collect::<Vec<_>>();
+        let tree = MerkleTree::new(leaves);
+
+        let (kol, amount) = tree.leaf(2).unwrap();
+        let leaf = leaf_hash(2, &kol, amount + 1);
+        assert!(!verify_proof(&tree.proof(2), &tree.root(), leaf));
+    }
+
+    #[test]
+    fn allowlist_proofs_only_verify_listed_kols() {
+        let kols = (0..5).map(|_| Pubkey::new_unique()).collect::<Vec<_>>();
+        let tree = MerkleTree::allowlist(&kols);
+
+        for (index, kol) in kols.iter().enumerate() {
+            let leaf = allowlist_leaf_hash(kol);
+            assert!(verify_proof(&tree.proof(index as u32), &tree.root(), leaf));
+        }
+
+        let outsider = allowlist_leaf_hash(&Pubkey::new_unique());
+        assert!(!verify_proof(&tree.proof(0), &tree.root(), outsider));
+        assert_eq!(tree.leaf(0), None);
+    }
+}
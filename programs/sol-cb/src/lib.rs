@@ -1,6 +1,21 @@
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::hash::hash;
-use anchor_spl::token::{self, Mint, TokenAccount, Transfer};
+use anchor_lang::system_program;
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use anchor_spl::token_2022::spl_token_2022::{
+    self,
+    extension::{
+        transfer_fee::{TransferFeeAmount, TransferFeeConfig},
+        BaseStateWithExtensions, ExtensionType, StateWithExtensions,
+    },
+};
+use anchor_spl::token_2022_extensions::transfer_fee::{self, HarvestWithheldTokensToMint};
+use anchor_spl::token_interface::{
+    self, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
+
+pub mod merkle;
+pub mod price_feed;
 
 declare_id!("9FqxB422dd2UCW7YPDL2GYNWXTisv7envZcmmVMHAU14");
 
@@ -11,6 +26,9 @@ pub enum CampaignStatus {
     Fulfilled,
     Unfulfilled,
     Discarded,
+    Submitted,
+    Disputed,
+    Resolved,
 }
 
 #[derive(AnchorDeserialize, AnchorSerialize, Clone, Copy, Debug, PartialEq)]
@@ -20,6 +38,81 @@ pub enum OpenCampaignStatus {
     Discarded,
 }
 
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Copy, Debug, PartialEq)]
+pub enum SubscriptionStatus {
+    Open,
+    Active,
+    Completed,
+    Terminated,
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Copy, Debug, PartialEq)]
+pub enum OpenCampaignPayoutMode {
+    Allocation, // Operator posts per-KOL allocations or a Merkle root
+    ProRata,    // Pool is split by engagement score
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Copy, Debug, PartialEq)]
+pub struct MerkleDistribution {
+    pub root: [u8; 32],
+    pub leaf_count: u32,   // Sizes the claim bitmap
+    pub total_amount: u64, // Sum of every leaf amount
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct EligibilityConfig {
+    pub allowlist_root: [u8; 32], // Zeroed means no allowlist
+    pub min_reputation: u64,      // 0 means no reputation requirement
+    pub required_mint: Pubkey,    // Default pubkey means no token gate
+}
+
+impl EligibilityConfig {
+    /// Checks every configured criterion for `kol`; unset criteria are skipped.
+    pub fn check(
+        &self,
+        kol: &Pubkey,
+        allowlist_proof: &[[u8; 32]],
+        kol_reputation: Option<&KolReputation>,
+        eligibility_token_account: Option<&TokenAccount>,
+    ) -> Result<()> {
+        if self.allowlist_root != [0u8; 32]
+            && !merkle::verify_proof(
+                allowlist_proof,
+                &self.allowlist_root,
+                merkle::allowlist_leaf_hash(kol),
+            )
+        {
+            return err!(sol_cb::CustomErrorCode::NotEligible);
+        }
+
+        if self.min_reputation > 0 {
+            match kol_reputation {
+                Some(reputation) if reputation.score >= self.min_reputation => {}
+                _ => return err!(sol_cb::CustomErrorCode::NotEligible),
+            }
+        }
+
+        if self.required_mint != Pubkey::default() {
+            match eligibility_token_account {
+                Some(token_account)
+                    if token_account.owner == *kol
+                        && token_account.mint == self.required_mint
+                        && token_account.amount > 0 => {}
+                _ => return err!(sol_cb::CustomErrorCode::NotEligible),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Copy, Debug, PartialEq)]
+pub struct BundleLeg {
+    pub token_mint: Pubkey,
+    pub amount: u64,   // Net amount received by the leg vault
+    pub settled: bool, // Paid out or refunded once the campaign reached a final status
+}
+
 #[account]
 pub struct Campaign {
     pub id: [u8; 4],
@@ -32,6 +125,129 @@ pub struct Campaign {
     pub promotion_ends_in: i64,
     pub amount_offered: u64,
     pub campaign_status: CampaignStatus,
+    pub kill_fee_bps: u16,
+    pub deliverable_hash: [u8; 32],
+    pub deliverable_uri: String,
+    pub submitted_at: i64,
+    pub review_ends_in: i64,
+    pub is_native: bool, // Escrow holds lamports in the campaign PDA instead of an SPL vault
+    pub bundle_legs: Vec<BundleLeg>, // Extra (mint, amount) legs escrowed next to the main token
+    pub usd_value: u64,  // 0 unless priced in USD (6 decimals) through the mint's price feed
+    pub usd_settled_at: i64, // When the token amount was re-priced at payout
+    pub vesting_cliff: i64, // Seconds after fulfilment before the KOL share starts vesting
+    pub vesting_duration: i64, // 0 pays the KOL share out immediately; bundle legs never vest
+    pub bond_amount: u64, // Posted by the KOL on acceptance, 0 if none
+    pub bond_settled: bool, // Returned or slashed once the campaign reached a final status
+    pub referrer: Pubkey, // Brought the creator, paid out of the platform fee
+    pub referrer_share_bps: u16, // Share of the platform fee, 0 if none
+    pub agency: Pubkey,  // Represents the KOL, paid out of the KOL share
+    pub agency_share_bps: u16, // Share of the KOL share, 0 if none
+}
+
+#[account]
+pub struct Vesting {
+    pub campaign: Pubkey,
+    pub creator: Pubkey, // Paid for the vesting account and its vault, gets the rent back
+    pub kol: Pubkey,
+    pub token_mint: Pubkey,
+    pub total_amount: u64, // KOL share moved in on fulfilment
+    pub claimed_amount: u64,
+    pub start_ts: i64, // 0 until the campaign is fulfilled
+    pub cliff_ts: i64,
+    pub end_ts: i64,
+}
+
+impl Vesting {
+    /// Amount released by `current_time`: nothing before the cliff, then linear until `end_ts`.
+    pub fn vested_amount(&self, current_time: i64) -> u64 {
+        if self.start_ts == 0 || current_time < self.cliff_ts {
+            return 0;
+        }
+
+        if current_time >= self.end_ts {
+            return self.total_amount;
+        }
+
+        ((self.total_amount as u128) * ((current_time - self.start_ts) as u128)
+            / ((self.end_ts - self.start_ts) as u128)) as u64
+    }
+}
+
+impl Space for Vesting {
+    const INIT_SPACE: usize = 8 + // Discriminator
+        32 + // campaign
+        32 + // creator
+        32 + // kol
+        32 + // token_mint
+        8 + // total_amount
+        8 + // claimed_amount
+        8 + // start_ts
+        8 + // cliff_ts
+        8 + // end_ts
+        64; // extra padding for safety
+}
+
+#[account]
+pub struct Subscription {
+    pub id: [u8; 4],
+    pub counter: u32,
+    pub created_at: i64,
+    pub creator_address: Pubkey,
+    pub selected_kol: Pubkey,
+    pub token_mint: Pubkey,
+    pub amount_per_period: u64,
+    pub period_length: i64, // Seconds per period, counted from `started_at`
+    pub total_periods: u32,
+    pub started_at: i64,  // Set when the KOL accepts
+    pub next_period: u32, // Lowest period index a deliverable can still be submitted for
+    pub periods_paid: u32,
+    pub pending_period: u32,
+    pub deliverable_hash: [u8; 32], // Zeroed unless a deliverable is awaiting payout
+    pub submitted_at: i64,
+    pub review_ends_in: i64,
+    pub status: SubscriptionStatus,
+    pub referrer: Pubkey, // Brought the creator, paid out of the platform fee
+    pub referrer_share_bps: u16, // Share of the platform fee, 0 if none
+    pub agency: Pubkey,   // Represents the KOL, paid out of the KOL share
+    pub agency_share_bps: u16, // Share of the KOL share, 0 if none
+}
+
+impl Subscription {
+    /// Index of the period `current_time` falls in. Periods roll over on their
+    /// own; a period without an accepted deliverable is simply never paid.
+    pub fn current_period(&self, current_time: i64) -> u64 {
+        (current_time.saturating_sub(self.started_at) / self.period_length) as u64
+    }
+
+    pub fn has_pending_deliverable(&self) -> bool {
+        self.deliverable_hash != [0u8; 32]
+    }
+}
+
+impl Space for Subscription {
+    const INIT_SPACE: usize = 8 + // Discriminator
+        4 + // id
+        4 + // counter
+        8 + // created_at
+        32 + // creator_address
+        32 + // selected_kol
+        32 + // token_mint
+        8 + // amount_per_period
+        8 + // period_length
+        4 + // total_periods
+        8 + // started_at
+        4 + // next_period
+        4 + // periods_paid
+        4 + // pending_period
+        32 + // deliverable_hash
+        8 + // submitted_at
+        8 + // review_ends_in
+        1 + // status
+        32 + // referrer
+        2 + // referrer_share_bps
+        32 + // agency
+        2 + // agency_share_bps
+        64; // extra padding for safety
 }
 
 #[account]
@@ -44,6 +260,59 @@ pub struct OpenCampaign {
     pub promotion_ends_in: i64,
     pub pool_amount: u64,
     pub campaign_status: OpenCampaignStatus,
+    pub max_participants: u32, // 0 means no cap
+    pub participant_count: u32,
+    pub allocated_amount: u64, // Sum of per-KOL allocations posted by the operator
+    pub claimed_amount: u64,
+    pub merkle_root: [u8; 32], // Zeroed unless settled with a Merkle root of (kol, amount) leaves
+    pub merkle_claimed_amount: u64,
+    pub extended_by: i64, // Total seconds the deadline has been pushed back
+    pub payout_mode: OpenCampaignPayoutMode,
+    pub total_engagement_score: u64,
+    pub scored_participants: u32,
+    pub claimed_participants: u32,
+    pub eligibility: EligibilityConfig,
+    pub reward_per_submission: u64, // 0 means no fixed bounty
+    pub max_payout_per_kol: u64,    // 0 means no cap
+    pub accepted_submissions: u32,
+    pub capped_excess: u64, // Pro-rata shares withheld by the cap, returned to the creator
+    pub merkle_leaf_count: u32,
+    pub merkle_total_amount: u64,
+    pub claims_end_at: i64, // Unclaimed rewards can be reclaimed by the creator after this
+}
+
+impl OpenCampaign {
+    /// Applies the per-KOL payout cap, if any, to `amount`.
+    pub fn capped_payout(&self, amount: u64) -> u64 {
+        if self.max_payout_per_kol == 0 {
+            return amount;
+        }
+
+        amount.min(self.max_payout_per_kol)
+    }
+
+    /// Pro-rata share of the pool for `engagement_score`, rounded down. The
+    /// rounding dust is swept to the treasury once everyone has claimed.
+    pub fn pro_rata_share(&self, engagement_score: u64) -> u64 {
+        if self.total_engagement_score == 0 {
+            return 0;
+        }
+
+        ((self.pool_amount as u128) * (engagement_score as u128)
+            / (self.total_engagement_score as u128)) as u64
+    }
+}
+
+#[account]
+pub struct Participation {
+    pub open_campaign: Pubkey,
+    pub kol: Pubkey,
+    pub joined_at: i64,
+    pub submission_hash: [u8; 32],
+    pub submitted_at: i64,
+    pub allocation: u64,
+    pub claimed: bool,
+    pub engagement_score: u64,
 }
 
 impl Space for Campaign {
@@ -58,6 +327,23 @@ impl Space for Campaign {
         8 + // promotion_ends_in
         8 + // amount_offered
         1 + // campaign_status
+        2 + // kill_fee_bps
+        32 + // deliverable_hash
+        4 + sol_cb::MAX_DELIVERABLE_URI_LENGTH + // deliverable_uri
+        8 + // submitted_at
+        8 + // review_ends_in
+        1 + // is_native
+        4 + sol_cb::MAX_BUNDLE_LEGS * (32 + 8 + 1) + // bundle_legs
+        8 + // usd_value
+        8 + // usd_settled_at
+        8 + // vesting_cliff
+        8 + // vesting_duration
+        8 + // bond_amount
+        1 + // bond_settled
+        32 + // referrer
+        2 + // referrer_share_bps
+        32 + // agency
+        2 + // agency_share_bps
         64; // extra padding for safety
 }
 
@@ -71,6 +357,77 @@ impl Space for OpenCampaign {
         8 + // promotion_ends_in
         8 + // pool_amount
         1 + // campaign_status
+        4 + // max_participants
+        4 + // participant_count
+        8 + // allocated_amount
+        8 + // claimed_amount
+        32 + // merkle_root
+        8 + // merkle_claimed_amount
+        8 + // extended_by
+        1 + // payout_mode
+        8 + // total_engagement_score
+        4 + // scored_participants
+        4 + // claimed_participants
+        32 + 8 + 32 + // eligibility
+        8 + // reward_per_submission
+        8 + // max_payout_per_kol
+        4 + // accepted_submissions
+        8 + // capped_excess
+        4 + // merkle_leaf_count
+        8 + // merkle_total_amount
+        8 + // claims_end_at
+        64; // extra padding for safety
+}
+
+#[account]
+pub struct ClaimBitmap {
+    pub open_campaign: Pubkey,
+    pub leaf_count: u32,
+    pub claimed: Vec<u8>, // One bit per Merkle leaf
+}
+
+impl ClaimBitmap {
+    pub fn space(leaf_count: u32) -> usize {
+        8 + // Discriminator
+        32 + // open_campaign
+        4 + // leaf_count
+        4 + (leaf_count as usize).div_ceil(8) // claimed
+    }
+
+    pub fn is_claimed(&self, index: u32) -> bool {
+        self.claimed[(index / 8) as usize] & (1 << (index % 8)) != 0
+    }
+
+    pub fn set_claimed(&mut self, index: u32) {
+        self.claimed[(index / 8) as usize] |= 1 << (index % 8);
+    }
+}
+
+#[account]
+pub struct KolReputation {
+    pub kol: Pubkey,
+    pub score: u64,
+    pub updated_at: i64,
+}
+
+impl Space for KolReputation {
+    const INIT_SPACE: usize = 8 + // Discriminator
+        32 + // kol
+        8 + // score
+        8 + // updated_at
+        64; // extra padding for safety
+}
+
+impl Space for Participation {
+    const INIT_SPACE: usize = 8 + // Discriminator
+        32 + // open_campaign
+        32 + // kol
+        8 + // joined_at
+        32 + // submission_hash
+        8 + // submitted_at
+        8 + // allocation
+        1 + // claimed
+        8 + // engagement_score
         64; // extra padding for safety
 }
 
@@ -78,8 +435,145 @@ impl Space for OpenCampaign {
 pub struct MarketplaceState {
     pub owner: Pubkey,
     pub campaign_counter: u32,
-    pub allowed_tokens: Vec<Pubkey>, // Allowed tokens for payments
-    pub token_decimals: Vec<u8>,     // Token decimals in same order as allowed_tokens
+    pub allowed_tokens: Vec<Pubkey>,    // Allowed tokens for payments
+    pub token_decimals: Vec<u8>,        // Token decimals in same order as allowed_tokens
+    pub review_window: i64,             // Seconds a creator has to review a submitted deliverable
+    pub arbiter: Pubkey,                // Resolves disputes between creators and KOLs
+    pub listing_fee_bps: u16,           // Kept from discarded open campaign pools
+    pub max_deadline_extension: i64,    // Max total seconds an open campaign deadline can move
+    pub oracle: Pubkey,                 // Posts engagement scores for open campaign participants
+    pub open_campaign_cooling_off: i64, // Seconds after creation a creator can cancel an open campaign
+    pub open_campaign_grace_period: i64, // Seconds after the deadline before anyone can expire an open campaign
+    pub bond_slash_treasury_bps: u16, // Share of a slashed KOL bond kept by the treasury, the rest goes to the creator
+    pub max_referrer_share_bps: u16,  // Cap on a referrer's share of the platform fee
+    pub max_agency_share_bps: u16,    // Cap on an agency's share of the KOL share
+    pub fee_tiers: Vec<FeeTier>,      // Volume-based platform fee discounts per mint
+    pub open_campaign_claim_window: i64, // Seconds participants have to claim once an open campaign settles
+}
+
+impl MarketplaceState {
+    /// Platform fee charged to `creator_profile` when settling in `token_mint`:
+    /// the admin override if any, otherwise the cheapest tier the creator's
+    /// settled volume has reached, falling back to the default share.
+    pub fn platform_fee_bps(&self, creator_profile: &CreatorProfile, token_mint: &Pubkey) -> u16 {
+        if let Some(fee_override_bps) = creator_profile.fee_override_bps {
+            return fee_override_bps;
+        }
+
+        let settled_volume = creator_profile.settled_volume_for(token_mint);
+        self.fee_tiers
+            .iter()
+            .filter(|tier| tier.token_mint == *token_mint && settled_volume >= tier.min_volume)
+            .map(|tier| tier.fee_bps)
+            .fold(sol_cb::OWNER_SHARE_PERCENTAGE as u16, u16::min)
+    }
+}
+
+#[account]
+pub struct PriceFeedConfig {
+    pub token_mint: Pubkey,
+    pub price_feed: Pubkey, // Pyth-layout price account quoting the mint in USD
+    pub max_staleness: i64, // Max seconds since the feed was last published
+    pub max_confidence_bps: u16, // Max confidence interval relative to the price
+}
+
+impl Space for PriceFeedConfig {
+    const INIT_SPACE: usize = 8 + // Discriminator
+        32 + // token_mint
+        32 + // price_feed
+        8 + // max_staleness
+        2 + // max_confidence_bps
+        64; // extra padding for safety
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Copy, Debug, PartialEq)]
+pub struct FeeTier {
+    pub token_mint: Pubkey,
+    pub min_volume: u64, // Settled volume in `token_mint` a creator needs to reach this tier
+    pub fee_bps: u16,
+}
+
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Copy, Debug, PartialEq)]
+pub struct MintVolume {
+    pub token_mint: Pubkey,
+    pub amount: u64,
+}
+
+#[account]
+pub struct CreatorProfile {
+    pub creator: Pubkey,
+    pub settled_volume: Vec<MintVolume>, // Cumulative amount settled per mint
+    pub settled_campaigns: u32,
+    pub fee_override_bps: Option<u16>, // Negotiated rate set by the admin, replaces the tiers
+}
+
+impl CreatorProfile {
+    pub fn settled_volume_for(&self, token_mint: &Pubkey) -> u64 {
+        self.settled_volume
+            .iter()
+            .find(|volume| volume.token_mint == *token_mint)
+            .map_or(0, |volume| volume.amount)
+    }
+
+    /// Counts a settled campaign and adds the `amount` it settled to the
+    /// creator's volume in its mint.
+    pub fn record_settlement(&mut self, campaign: &Campaign, amount: u64) {
+        self.settled_campaigns = self.settled_campaigns.saturating_add(1);
+        self.record_volume(&campaign.creator_address, &campaign.token_mint, amount);
+    }
+
+    /// Adds `amount` settled by `creator` to their volume in `token_mint`.
+    pub fn record_volume(&mut self, creator: &Pubkey, token_mint: &Pubkey, amount: u64) {
+        self.creator = *creator;
+        if let Some(volume) = self
+            .settled_volume
+            .iter_mut()
+            .find(|volume| volume.token_mint == *token_mint)
+        {
+            volume.amount = volume.amount.saturating_add(amount);
+        } else if self.settled_volume.len() < sol_cb::MAX_PROFILE_MINTS {
+            self.settled_volume.push(MintVolume {
+                token_mint: *token_mint,
+                amount,
+            });
+        } else {
+            msg!("Volume for {} not tracked, profile is full", token_mint);
+        }
+    }
+}
+
+impl Space for CreatorProfile {
+    const INIT_SPACE: usize = 8 + // Discriminator
+        32 + // creator
+        4 + sol_cb::MAX_PROFILE_MINTS * (32 + 8) + // settled_volume
+        4 + // settled_campaigns
+        1 + 2 + // fee_override_bps
+        64; // extra padding for safety
+}
+
+#[account]
+pub struct Dispute {
+    pub campaign: Pubkey,
+    pub opened_by: Pubkey,
+    pub opened_at: i64,
+    pub creator_evidence_hash: [u8; 32],
+    pub kol_evidence_hash: [u8; 32],
+    pub resolved_at: i64,
+    pub kol_amount: u64,
+    pub creator_amount: u64,
+}
+
+impl Space for Dispute {
+    const INIT_SPACE: usize = 8 + // Discriminator
+        32 + // campaign
+        32 + // opened_by
+        8 + // opened_at
+        32 + // creator_evidence_hash
+        32 + // kol_evidence_hash
+        8 + // resolved_at
+        8 + // kol_amount
+        8 + // creator_amount
+        64; // extra padding for safety
 }
 
 impl Space for MarketplaceState {
@@ -88,6 +582,18 @@ impl Space for MarketplaceState {
         4 + // campaign_counter
         (32 * 20) + // allowed_tokens (max 20 tokens)
         20 + // token_decimals (max 20 tokens)
+        8 + // review_window
+        32 + // arbiter
+        2 + // listing_fee_bps
+        8 + // max_deadline_extension
+        32 + // oracle
+        8 + // open_campaign_cooling_off
+        8 + // open_campaign_grace_period
+        2 + // bond_slash_treasury_bps
+        2 + // max_referrer_share_bps
+        2 + // max_agency_share_bps
+        4 + sol_cb::MAX_FEE_TIERS * (32 + 8 + 2) + // fee_tiers
+        8 + // open_campaign_claim_window
         64; // extra padding for safety
 }
 
@@ -99,6 +605,17 @@ pub mod sol_cb {
     pub const DIVIDER: u64 = 10_000;
     pub const KOL_SHARE_PERCENTAGE: u64 = 9000; // 90% of the total amount
     pub const OWNER_SHARE_PERCENTAGE: u64 = 1000; // 10% of the total amount
+    pub const MAX_DELIVERABLE_URI_LENGTH: usize = 200;
+    pub const DEFAULT_REVIEW_WINDOW: i64 = 3 * 86400; // 3 days
+    pub const DEFAULT_MAX_DEADLINE_EXTENSION: i64 = 30 * 86400; // 30 days
+    pub const DEFAULT_OPEN_CAMPAIGN_COOLING_OFF: i64 = 86400; // 1 day
+    pub const DEFAULT_OPEN_CAMPAIGN_GRACE_PERIOD: i64 = 7 * 86400; // 7 days
+    pub const DEFAULT_OPEN_CAMPAIGN_CLAIM_WINDOW: i64 = 30 * 86400; // 30 days
+    pub const MAX_MERKLE_LEAVES: u32 = 64_000; // Keeps the claim bitmap under the 10KB init limit
+    pub const MAX_BUNDLE_LEGS: usize = 3;
+    pub const MAX_FEE_TIERS: usize = 10;
+    pub const MAX_SUBSCRIPTION_PERIODS: u32 = 52; // A year of weekly posts
+    pub const MAX_PROFILE_MINTS: usize = 20; // Matches the allowed token capacity
 
     // ------------------ ERRORS ------------------
     #[error_code]
@@ -127,6 +644,90 @@ pub mod sol_cb {
         TokenAlreadyAllowed,
         #[msg("Token not found")]
         TokenNotFound,
+        #[msg("Invalid deliverable URI")]
+        InvalidDeliverableUri,
+        #[msg("Review window is still active")]
+        ReviewWindowActive,
+        #[msg("Review window has expired")]
+        ReviewWindowExpired,
+        #[msg("Campaign escrow is not empty")]
+        EscrowNotEmpty,
+        #[msg("Participant limit reached")]
+        ParticipantLimitReached,
+        #[msg("Allocations exceed the campaign pool")]
+        AllocationExceedsPool,
+        #[msg("Nothing to claim")]
+        NothingToClaim,
+        #[msg("Invalid Merkle proof")]
+        InvalidMerkleProof,
+        #[msg("Reward already claimed")]
+        AlreadyClaimed,
+        #[msg("Claims exceed the campaign pool")]
+        ClaimExceedsPool,
+        #[msg("Deadline extension exceeds the allowed maximum")]
+        ExtensionTooLong,
+        #[msg("Not supported by this campaign's payout mode")]
+        InvalidPayoutMode,
+        #[msg("No engagement scores recorded")]
+        NoEngagementScores,
+        #[msg("Not every participant has claimed yet")]
+        ClaimsOutstanding,
+        #[msg("KOL does not meet the campaign eligibility criteria")]
+        NotEligible,
+        #[msg("Payout exceeds the per-KOL cap")]
+        PayoutCapExceeded,
+        #[msg("No pending submission")]
+        NoPendingSubmission,
+        #[msg("Open campaign can no longer be cancelled")]
+        CancellationWindowClosed,
+        #[msg("Open campaign grace period has not ended yet")]
+        GracePeriodActive,
+        #[msg("Token mint uses an unsupported extension")]
+        UnsupportedMintExtension,
+        #[msg("Campaign escrow asset does not match this instruction")]
+        EscrowAssetMismatch,
+        #[msg("Too many bundle legs")]
+        TooManyBundleLegs,
+        #[msg("Bundle leg already settled")]
+        BundleLegSettled,
+        #[msg("Bundle legs are still unsettled")]
+        BundleLegsOutstanding,
+        #[msg("Invalid price feed")]
+        InvalidPriceFeed,
+        #[msg("Price feed is stale")]
+        StalePrice,
+        #[msg("Price confidence interval is too wide")]
+        PriceConfidenceTooWide,
+        #[msg("Price feed accounts are required for this campaign")]
+        PriceFeedAccountsMissing,
+        #[msg("Vesting accounts are required for this campaign")]
+        VestingAccountsMissing,
+        #[msg("Bond accounts are required to post a bond")]
+        BondAccountsMissing,
+        #[msg("No unsettled bond")]
+        NoBond,
+        #[msg("KOL bond is still unsettled")]
+        BondOutstanding,
+        #[msg("Revenue share exceeds the allowed maximum")]
+        RevenueShareTooHigh,
+        #[msg("Revenue share account is missing or invalid")]
+        InvalidRevenueShareAccount,
+        #[msg("Too many fee tiers")]
+        TooManyFeeTiers,
+        #[msg("Invalid subscription status")]
+        InvalidSubscriptionStatus,
+        #[msg("A deliverable is still awaiting payout")]
+        SubmissionPending,
+        #[msg("Period has already been settled")]
+        PeriodAlreadySettled,
+        #[msg("Bundle leg accounts are required to pay out this campaign")]
+        BundleLegAccountsMissing,
+        #[msg("Claim window has closed")]
+        ClaimWindowClosed,
+        #[msg("Claim window is still open")]
+        ClaimWindowActive,
+        #[msg("Not supported for native SOL campaigns")]
+        NativeCampaignUnsupported,
     }
 
     pub fn initialize(
@@ -144,6 +745,16 @@ pub mod sol_cb {
         ctx.accounts.marketplace_state.campaign_counter = 0;
         ctx.accounts.marketplace_state.allowed_tokens = allowed_tokens;
         ctx.accounts.marketplace_state.token_decimals = token_decimals;
+        ctx.accounts.marketplace_state.review_window = DEFAULT_REVIEW_WINDOW;
+        ctx.accounts.marketplace_state.arbiter = ctx.accounts.owner.key();
+        ctx.accounts.marketplace_state.max_deadline_extension = DEFAULT_MAX_DEADLINE_EXTENSION;
+        ctx.accounts.marketplace_state.oracle = ctx.accounts.owner.key();
+        ctx.accounts.marketplace_state.open_campaign_cooling_off =
+            DEFAULT_OPEN_CAMPAIGN_COOLING_OFF;
+        ctx.accounts.marketplace_state.open_campaign_grace_period =
+            DEFAULT_OPEN_CAMPAIGN_GRACE_PERIOD;
+        ctx.accounts.marketplace_state.open_campaign_claim_window =
+            DEFAULT_OPEN_CAMPAIGN_CLAIM_WINDOW;
         Ok(())
     }
 
@@ -153,16 +764,23 @@ pub mod sol_cb {
         offering_amount: u64,
         promotion_ends_in: i64,
         offer_ends_in: i64,
+        kill_fee_bps: u16,
     ) -> Result<()> {
         if offering_amount == 0 {
             return err!(CustomErrorCode::InvalidAmount);
         }
 
+        if kill_fee_bps as u64 > DIVIDER {
+            return err!(CustomErrorCode::InvalidParameters);
+        }
+
         let current_time = Clock::get()?.unix_timestamp;
         if offer_ends_in <= current_time || promotion_ends_in <= current_time {
             return err!(CustomErrorCode::InvalidTimeParameters);
         }
 
+        check_mint_extensions(&ctx.accounts.token_mint)?;
+
         // Generate a campaign ID by creating a hash of creator key and timestamp
         let creator_key = ctx.accounts.creator.key();
         let counter = ctx.accounts.marketplace_state.campaign_counter;
@@ -194,8 +812,10 @@ pub mod sol_cb {
         campaign.selected_kol = selected_kol;
         campaign.offer_ends_in = offer_ends_in;
         campaign.promotion_ends_in = promotion_ends_in;
-        campaign.amount_offered = offering_amount;
+        // Escrow accounting tracks what the vault holds once funded
+        campaign.amount_offered = net_of_transfer_fee(&ctx.accounts.token_mint, offering_amount)?;
         campaign.campaign_status = CampaignStatus::Open;
+        campaign.kill_fee_bps = kill_fee_bps;
 
         msg!(
             "Campaign created with ID: {:?}, creator: {:?} and counter: {:?}",
@@ -213,11 +833,16 @@ pub mod sol_cb {
         promotion_ends_in: i64,
         offer_ends_in: i64,
         new_amount_offered: u64,
+        kill_fee_bps: u16,
     ) -> Result<()> {
         if selected_kol == Pubkey::default() {
             return err!(CustomErrorCode::InvalidKolAddress);
         }
 
+        if kill_fee_bps as u64 > DIVIDER {
+            return err!(CustomErrorCode::InvalidParameters);
+        }
+
         let campaign = &mut ctx.accounts.campaign;
 
         if campaign.campaign_status != CampaignStatus::Open {
@@ -232,7 +857,11 @@ pub mod sol_cb {
         campaign.selected_kol = selected_kol;
         campaign.promotion_ends_in = promotion_ends_in;
         campaign.offer_ends_in = offer_ends_in;
-        campaign.amount_offered = new_amount_offered;
+        campaign.amount_offered =
+            net_of_transfer_fee(&ctx.accounts.token_mint, new_amount_offered)?;
+        campaign.kill_fee_bps = kill_fee_bps;
+        // An explicit token amount replaces any USD pricing
+        campaign.usd_value = 0;
 
         msg!(
             "Campaign updated with ID: {:?}, updated by: {:?}",
@@ -244,48 +873,73 @@ pub mod sol_cb {
     }
 
     pub fn discard_project_campaign(ctx: Context<DiscardProjectCampaign>) -> Result<()> {
-        // Extract values before mutable borrow
-        let creator_address = ctx.accounts.campaign.creator_address;
-        let counter = ctx.accounts.campaign.counter;
-        let campaign_balance = ctx.accounts.campaign_token_account.amount;
+        let decimals = ctx.accounts.token_mint.decimals;
 
-        if creator_address != ctx.accounts.creator.key() {
+        if ctx.accounts.campaign.creator_address != ctx.accounts.creator.key() {
             return err!(CustomErrorCode::Unauthorized);
         }
 
-        if campaign_balance > 0 {
-            let bump = ctx.bumps.campaign;
-            let seeds = &[
-                b"campaign",
-                creator_address.as_ref(),
-                &counter.to_le_bytes(),
-                &[bump],
-            ];
+        let campaign_balance = ctx.accounts.campaign_token_account.amount;
 
-            ctx.accounts.campaign.campaign_status = CampaignStatus::Discarded;
+        // Before acceptance the creator gets everything back; once the KOL has
+        // accepted they are owed the kill fee. Submitted or disputed campaigns
+        // can no longer be pulled back unilaterally.
+        let kill_fee = match ctx.accounts.campaign.campaign_status {
+            CampaignStatus::Open => 0,
+            CampaignStatus::Accepted => ctx
+                .accounts
+                .campaign
+                .amount_offered
+                .checked_mul(ctx.accounts.campaign.kill_fee_bps as u64)
+                .unwrap()
+                .checked_div(DIVIDER)
+                .unwrap()
+                .min(campaign_balance),
+            _ => return err!(CustomErrorCode::InvalidCampaignStatus),
+        };
+        let refund_amount = campaign_balance - kill_fee;
 
-            let signer_seeds = &[&seeds[..]];
+        let bump = ctx.bumps.campaign;
+        transfer_from_campaign(
+            &ctx.accounts.campaign,
+            bump,
+            &ctx.accounts.campaign_token_account,
+            &ctx.accounts.kol_token_account,
+            &ctx.accounts.token_mint,
+            decimals,
+            &ctx.accounts.token_program,
+            kill_fee,
+        )?;
+        transfer_from_campaign(
+            &ctx.accounts.campaign,
+            bump,
+            &ctx.accounts.campaign_token_account,
+            &ctx.accounts.creator_token_account,
+            &ctx.accounts.token_mint,
+            decimals,
+            &ctx.accounts.token_program,
+            refund_amount,
+        )?;
 
-            token::transfer(
-                CpiContext::new_with_signer(
-                    ctx.accounts.token_program.to_account_info(),
-                    Transfer {
-                        from: ctx.accounts.campaign_token_account.to_account_info(),
-                        to: ctx.accounts.creator_token_account.to_account_info(),
-                        authority: ctx.accounts.campaign.to_account_info(),
-                    },
-                    signer_seeds,
-                ),
-                campaign_balance,
-            )?;
+        ctx.accounts.campaign.campaign_status = CampaignStatus::Discarded;
 
-            msg!("Transferred {} tokens back to creator", campaign_balance);
-        }
+        msg!(
+            "Transferred {} tokens back to creator and {} kill fee to KOL",
+            refund_amount,
+            kill_fee
+        );
 
         Ok(())
     }
 
-    pub fn accept_project_campaign(ctx: Context<AcceptProjectCampaign>) -> Result<()> {
+    pub fn accept_project_campaign(
+        ctx: Context<AcceptProjectCampaign>,
+        bond_amount: u64,
+    ) -> Result<()> {
+        if bond_amount > 0 {
+            post_kol_bond(&ctx, bond_amount)?;
+        }
+
         let campaign = &mut ctx.accounts.campaign;
         let current_time = Clock::get()?.unix_timestamp;
 
@@ -302,87 +956,104 @@ pub mod sol_cb {
         }
 
         campaign.campaign_status = CampaignStatus::Accepted;
+        campaign.bond_amount = bond_amount;
 
         msg!(
-            "Campaign accepted with ID: {:?}, accepted by: {:?}",
+            "Campaign accepted with ID: {:?}, accepted by: {:?} with a bond of {}",
             campaign.id,
-            ctx.accounts.kol.key()
+            ctx.accounts.kol.key(),
+            bond_amount
         );
 
         Ok(())
     }
 
-    pub fn fulfil_project_campaign(ctx: Context<FulfilProjectCampaign>) -> Result<()> {
-        // Check campaign status first
-        if ctx.accounts.campaign.campaign_status != CampaignStatus::Accepted {
-            return err!(CustomErrorCode::InvalidCampaignStatus);
+    pub fn submit_deliverable(
+        ctx: Context<SubmitDeliverable>,
+        content_hash: [u8; 32],
+        uri: String,
+    ) -> Result<()> {
+        if uri.is_empty() || uri.len() > MAX_DELIVERABLE_URI_LENGTH {
+            return err!(CustomErrorCode::InvalidDeliverableUri);
         }
 
-        let bump = ctx.bumps.campaign;
+        let campaign = &mut ctx.accounts.campaign;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        if campaign.selected_kol != ctx.accounts.kol.key() {
+            return err!(CustomErrorCode::Unauthorized);
+        }
 
-        // Extract all the data we need before doing any mutable operations
-        let creator_address = ctx.accounts.campaign.creator_address;
-        let counter = ctx.accounts.campaign.counter;
-        let total_amount = ctx.accounts.campaign.amount_offered;
+        if campaign.campaign_status != CampaignStatus::Accepted {
+            return err!(CustomErrorCode::InvalidCampaignStatus);
+        }
 
-        // Calculate amounts based on percentages
-        let kol_amount = total_amount
-            .checked_mul(KOL_SHARE_PERCENTAGE)
-            .unwrap()
-            .checked_div(DIVIDER)
-            .unwrap();
-        let owner_amount = total_amount
-            .checked_mul(OWNER_SHARE_PERCENTAGE)
-            .unwrap()
-            .checked_div(DIVIDER)
+        if current_time > campaign.promotion_ends_in {
+            return err!(CustomErrorCode::CampaignExpired);
+        }
+
+        campaign.deliverable_hash = content_hash;
+        campaign.deliverable_uri = uri;
+        campaign.submitted_at = current_time;
+        campaign.review_ends_in = current_time
+            .checked_add(ctx.accounts.marketplace_state.review_window)
             .unwrap();
+        campaign.campaign_status = CampaignStatus::Submitted;
 
-        // Get campaign ID for logging
-        let campaign_id = ctx.accounts.campaign.id;
+        emit!(DeliverableSubmitted {
+            campaign_id: campaign.id,
+            submitted_by: ctx.accounts.kol.key(),
+            content_hash,
+            submitted_at: current_time,
+        });
 
-        // Update campaign status
-        ctx.accounts.campaign.campaign_status = CampaignStatus::Fulfilled;
+        msg!(
+            "Deliverable submitted for campaign ID: {:?}, submitted by: {:?}",
+            campaign.id,
+            ctx.accounts.kol.key()
+        );
 
-        // Set up seeds for signing
-        let seeds = &[
-            b"campaign",
-            creator_address.as_ref(),
-            &counter.to_le_bytes(),
-            &[bump],
-        ];
-        let signer_seeds = &[&seeds[..]];
+        Ok(())
+    }
 
-        // Transfer tokens to KOL (90%)
-        token::transfer(
-            CpiContext::new_with_signer(
-                ctx.accounts.token_program.to_account_info(),
-                Transfer {
-                    from: ctx.accounts.campaign_token_account.to_account_info(),
-                    to: ctx.accounts.kol_token_account.to_account_info(),
-                    authority: ctx.accounts.campaign.to_account_info(),
-                },
-                signer_seeds,
-            ),
-            kol_amount,
-        )?;
+    pub fn fulfil_project_campaign<'info>(
+        ctx: Context<'_, '_, 'info, 'info, FulfilProjectCampaign<'info>>,
+    ) -> Result<()> {
+        // Check campaign status first; a submitted deliverable can be settled as well
+        let campaign_status = ctx.accounts.campaign.campaign_status;
+        if campaign_status != CampaignStatus::Accepted
+            && campaign_status != CampaignStatus::Submitted
+        {
+            return err!(CustomErrorCode::InvalidCampaignStatus);
+        }
 
-        // Transfer tokens to Owner (10%)
-        token::transfer(
-            CpiContext::new_with_signer(
-                ctx.accounts.token_program.to_account_info(),
-                Transfer {
-                    from: ctx.accounts.campaign_token_account.to_account_info(),
-                    to: ctx.accounts.owner_token_account.to_account_info(),
-                    authority: ctx.accounts.campaign.to_account_info(),
-                },
-                signer_seeds,
-            ),
-            owner_amount,
+        let accounts = &mut *ctx.accounts;
+        let (kol_amount, owner_amount) = settle_direct_campaign(
+            DirectPayout {
+                marketplace_state: &accounts.marketplace_state,
+                campaign: &mut accounts.campaign,
+                campaign_bump: ctx.bumps.campaign,
+                creator_profile: &mut accounts.creator_profile,
+                campaign_token_account: &mut accounts.campaign_token_account,
+                kol_token_account: &accounts.kol_token_account,
+                vesting: &mut accounts.vesting,
+                vesting_token_account: &accounts.vesting_token_account,
+                owner_token_account: &accounts.owner_token_account,
+                referrer_token_account: accounts.referrer_token_account.as_ref(),
+                agency_token_account: accounts.agency_token_account.as_ref(),
+                price_feed_config: &accounts.price_feed_config,
+                price_feed: &accounts.price_feed,
+                creator_token_account: &accounts.creator_token_account,
+                token_mint: &accounts.token_mint,
+                token_program: &accounts.token_program,
+                remaining_accounts: ctx.remaining_accounts,
+            },
+            None,
         )?;
 
         msg!(
             "Campaign fulfilled with ID: {:?}. Transferred {} to KOL and {} to owner",
-            campaign_id,
+            ctx.accounts.campaign.id,
             kol_amount,
             owner_amount
         );
@@ -390,194 +1061,6091 @@ pub mod sol_cb {
         Ok(())
     }
 
-    pub fn create_open_campaign(
-        ctx: Context<CreateOpenCampaign>,
-        promotion_ends_in: i64,
-        pool_amount: u64,
+    pub fn approve_deliverable<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ApproveDeliverable<'info>>,
     ) -> Result<()> {
-        if pool_amount == 0 {
-            return err!(CustomErrorCode::InvalidAmount);
+        if ctx.accounts.campaign.creator_address != ctx.accounts.creator.key() {
+            return err!(CustomErrorCode::Unauthorized);
         }
 
-        let current_time = Clock::get()?.unix_timestamp;
-        if promotion_ends_in <= current_time {
-            return err!(CustomErrorCode::InvalidTimeParameters);
+        if ctx.accounts.campaign.campaign_status != CampaignStatus::Submitted {
+            return err!(CustomErrorCode::InvalidCampaignStatus);
         }
 
-        // Generate campaign ID similar to regular campaigns
-        let creator_key = ctx.accounts.creator.key();
-        let counter = ctx.accounts.marketplace_state.campaign_counter;
-
-        let mut data_to_hash = vec![];
-        data_to_hash.extend_from_slice(&current_time.to_le_bytes());
-        data_to_hash.extend_from_slice(creator_key.as_ref());
-        data_to_hash.extend_from_slice(&counter.to_le_bytes());
+        // The creator signs, so a USD price shortfall is topped up from them
+        let accounts = &mut *ctx.accounts;
+        let (kol_amount, owner_amount) = settle_direct_campaign(
+            DirectPayout {
+                marketplace_state: &accounts.marketplace_state,
+                campaign: &mut accounts.campaign,
+                campaign_bump: ctx.bumps.campaign,
+                creator_profile: &mut accounts.creator_profile,
+                campaign_token_account: &mut accounts.campaign_token_account,
+                kol_token_account: &accounts.kol_token_account,
+                vesting: &mut accounts.vesting,
+                vesting_token_account: &accounts.vesting_token_account,
+                owner_token_account: &accounts.owner_token_account,
+                referrer_token_account: accounts.referrer_token_account.as_ref(),
+                agency_token_account: accounts.agency_token_account.as_ref(),
+                price_feed_config: &accounts.price_feed_config,
+                price_feed: &accounts.price_feed,
+                creator_token_account: &accounts.creator_token_account,
+                token_mint: &accounts.token_mint,
+                token_program: &accounts.token_program,
+                remaining_accounts: ctx.remaining_accounts,
+            },
+            Some(&accounts.creator),
+        )?;
 
-        let hashed = hash(&data_to_hash).to_bytes();
-        let id_data = [hashed[0], hashed[1], hashed[2], hashed[3]];
+        msg!(
+            "Deliverable approved for campaign ID: {:?}. Transferred {} to KOL and {} to owner",
+            ctx.accounts.campaign.id,
+            kol_amount,
+            owner_amount
+        );
 
-        // Increment the counter
-        ctx.accounts.marketplace_state.campaign_counter = ctx
-            .accounts
-            .marketplace_state
-            .campaign_counter
-            .checked_add(1)
-            .unwrap();
+        Ok(())
+    }
 
-        let campaign = &mut ctx.accounts.open_campaign;
-        campaign.id = id_data;
-        campaign.counter = counter;
-        campaign.created_at = current_time;
-        campaign.creator_address = ctx.accounts.creator.key();
-        campaign.token_mint = ctx.accounts.token_mint.key();
-        campaign.promotion_ends_in = promotion_ends_in;
-        campaign.pool_amount = pool_amount;
-        campaign.campaign_status = OpenCampaignStatus::Published;
+    pub fn release_payout<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ReleasePayout<'info>>,
+    ) -> Result<()> {
+        if ctx.accounts.campaign.campaign_status != CampaignStatus::Submitted {
+            return err!(CustomErrorCode::InvalidCampaignStatus);
+        }
+
+        // Anyone can release once the creator let the review window lapse
+        let current_time = Clock::get()?.unix_timestamp;
+        if current_time < ctx.accounts.campaign.review_ends_in {
+            return err!(CustomErrorCode::ReviewWindowActive);
+        }
+
+        // Releasing a USD campaign also picks its rate, so only the parties
+        // get to time it; a creator releasing tops up any shortfall
+        let caller = ctx.accounts.caller.key();
+        let is_creator = caller == ctx.accounts.campaign.creator_address;
+        if ctx.accounts.campaign.usd_value > 0
+            && !is_creator
+            && caller != ctx.accounts.campaign.selected_kol
+        {
+            return err!(CustomErrorCode::Unauthorized);
+        }
+
+        let accounts = &mut *ctx.accounts;
+        let (kol_amount, owner_amount) = settle_direct_campaign(
+            DirectPayout {
+                marketplace_state: &accounts.marketplace_state,
+                campaign: &mut accounts.campaign,
+                campaign_bump: ctx.bumps.campaign,
+                creator_profile: &mut accounts.creator_profile,
+                campaign_token_account: &mut accounts.campaign_token_account,
+                kol_token_account: &accounts.kol_token_account,
+                vesting: &mut accounts.vesting,
+                vesting_token_account: &accounts.vesting_token_account,
+                owner_token_account: &accounts.owner_token_account,
+                referrer_token_account: accounts.referrer_token_account.as_ref(),
+                agency_token_account: accounts.agency_token_account.as_ref(),
+                price_feed_config: &accounts.price_feed_config,
+                price_feed: &accounts.price_feed,
+                creator_token_account: &accounts.creator_token_account,
+                token_mint: &accounts.token_mint,
+                token_program: &accounts.token_program,
+                remaining_accounts: ctx.remaining_accounts,
+            },
+            is_creator.then_some(&accounts.caller),
+        )?;
 
         msg!(
-            "Open campaign created with ID: {:?}, creator: {:?} and counter: {:?}",
-            id_data,
-            ctx.accounts.creator.key(),
-            counter
+            "Payout released for campaign ID: {:?} by {:?}. Transferred {} to KOL and {} to owner",
+            ctx.accounts.campaign.id,
+            ctx.accounts.caller.key(),
+            kol_amount,
+            owner_amount
         );
 
         Ok(())
     }
 
-    pub fn complete_open_campaign(
-        ctx: Context<CompleteOpenCampaign>,
-        is_fulfilled: bool,
-    ) -> Result<()> {
-        // Check authorization first
-        if ctx.accounts.marketplace_state.owner != ctx.accounts.owner.key() {
+    pub fn open_dispute(ctx: Context<OpenDispute>, evidence_hash: [u8; 32]) -> Result<()> {
+        let party = ctx.accounts.party.key();
+        let campaign = &mut ctx.accounts.campaign;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        let is_creator = party == campaign.creator_address;
+        if !is_creator && party != campaign.selected_kol {
             return err!(CustomErrorCode::Unauthorized);
         }
 
-        // Store the status check result before mutable borrow
-        let is_published =
-            ctx.accounts.open_campaign.campaign_status == OpenCampaignStatus::Published;
-        if !is_published {
-            return err!(CustomErrorCode::InvalidOpenCampaignStatus);
+        match campaign.campaign_status {
+            CampaignStatus::Accepted => {}
+            // Creators must dispute a submission before the review window lapses
+            CampaignStatus::Submitted => {
+                if is_creator && current_time >= campaign.review_ends_in {
+                    return err!(CustomErrorCode::ReviewWindowExpired);
+                }
+            }
+            _ => return err!(CustomErrorCode::InvalidCampaignStatus),
         }
 
-        // Get amount before mutable borrow
-        let pool_amount = ctx.accounts.open_campaign.pool_amount;
+        campaign.campaign_status = CampaignStatus::Disputed;
 
-        // Update status
-        ctx.accounts.open_campaign.campaign_status = if is_fulfilled {
-            OpenCampaignStatus::Fulfilled
+        let dispute = &mut ctx.accounts.dispute;
+        dispute.campaign = campaign.key();
+        dispute.opened_by = party;
+        dispute.opened_at = current_time;
+        if is_creator {
+            dispute.creator_evidence_hash = evidence_hash;
         } else {
-            OpenCampaignStatus::Discarded
-        };
+            dispute.kol_evidence_hash = evidence_hash;
+        }
 
-        // Transfer pool amount to owner
-        let bump = ctx.bumps.open_campaign;
-        let seeds = &[
-            b"open_campaign",
-            ctx.accounts.open_campaign.creator_address.as_ref(),
-            &ctx.accounts.open_campaign.counter.to_le_bytes(),
-            &[bump],
-        ];
+        emit!(DisputeOpened {
+            campaign_id: campaign.id,
+            opened_by: party,
+            evidence_hash,
+        });
+
+        msg!(
+            "Dispute opened for campaign ID: {:?} by {:?}",
+            campaign.id,
+            party
+        );
+
+        Ok(())
+    }
+
+    pub fn submit_dispute_evidence(
+        ctx: Context<SubmitDisputeEvidence>,
+        evidence_hash: [u8; 32],
+    ) -> Result<()> {
+        let party = ctx.accounts.party.key();
+        let campaign = &ctx.accounts.campaign;
+
+        if campaign.campaign_status != CampaignStatus::Disputed {
+            return err!(CustomErrorCode::InvalidCampaignStatus);
+        }
+
+        let dispute = &mut ctx.accounts.dispute;
+        if party == campaign.creator_address {
+            dispute.creator_evidence_hash = evidence_hash;
+        } else if party == campaign.selected_kol {
+            dispute.kol_evidence_hash = evidence_hash;
+        } else {
+            return err!(CustomErrorCode::Unauthorized);
+        }
+
+        msg!(
+            "Dispute evidence recorded for campaign ID: {:?} by {:?}",
+            campaign.id,
+            party
+        );
+
+        Ok(())
+    }
+
+    pub fn resolve_dispute(ctx: Context<ResolveDispute>, kol_amount: u64) -> Result<()> {
+        let decimals = ctx.accounts.token_mint.decimals;
+
+        if ctx.accounts.campaign.campaign_status != CampaignStatus::Disputed {
+            return err!(CustomErrorCode::InvalidCampaignStatus);
+        }
+
+        // The arbiter splits whatever is escrowed; the creator gets the rest back
+        let campaign_balance = ctx.accounts.campaign_token_account.amount;
+        if kol_amount > campaign_balance {
+            return err!(CustomErrorCode::InvalidAmount);
+        }
+        let creator_amount = campaign_balance - kol_amount;
+
+        let fee_bps = ctx.accounts.marketplace_state.platform_fee_bps(
+            &ctx.accounts.creator_profile,
+            &ctx.accounts.campaign.token_mint,
+        );
+
+        // Vesting campaigns route the KOL share into the vesting vault
+        let kol_destination = vesting_destination(
+            &ctx.accounts.campaign,
+            &ctx.accounts.vesting,
+            &ctx.accounts.vesting_token_account,
+            &ctx.accounts.kol_token_account,
+        )?;
+        // Platform fee and revenue shares only apply to the KOL portion
+        let (kol_net_amount, owner_amount) = pay_out_campaign(
+            &ctx.accounts.campaign,
+            ctx.bumps.campaign,
+            &ctx.accounts.campaign_token_account,
+            kol_destination,
+            &ctx.accounts.owner_token_account,
+            ctx.accounts.referrer_token_account.as_ref(),
+            ctx.accounts.agency_token_account.as_ref(),
+            kol_amount,
+            fee_bps,
+            &ctx.accounts.token_mint,
+            decimals,
+            &ctx.accounts.token_program,
+        )?;
+        transfer_from_campaign(
+            &ctx.accounts.campaign,
+            ctx.bumps.campaign,
+            &ctx.accounts.campaign_token_account,
+            &ctx.accounts.creator_token_account,
+            &ctx.accounts.token_mint,
+            decimals,
+            &ctx.accounts.token_program,
+            creator_amount,
+        )?;
+        start_vesting(
+            &ctx.accounts.campaign,
+            &mut ctx.accounts.vesting,
+            kol_net_amount,
+        )?;
+        ctx.accounts
+            .creator_profile
+            .record_settlement(&ctx.accounts.campaign, kol_amount);
+
+        ctx.accounts.campaign.campaign_status = CampaignStatus::Resolved;
+
+        let dispute = &mut ctx.accounts.dispute;
+        dispute.resolved_at = Clock::get()?.unix_timestamp;
+        dispute.kol_amount = kol_amount;
+        dispute.creator_amount = creator_amount;
+
+        emit!(DisputeResolved {
+            campaign_id: ctx.accounts.campaign.id,
+            kol_amount,
+            creator_amount,
+        });
+
+        msg!(
+            "Dispute resolved for campaign ID: {:?}. Transferred {} to KOL, {} to owner and {} to creator",
+            ctx.accounts.campaign.id,
+            kol_net_amount,
+            owner_amount,
+            creator_amount
+        );
+
+        Ok(())
+    }
+
+    pub fn price_campaign_in_usd(ctx: Context<PriceCampaignInUsd>, usd_value: u64) -> Result<()> {
+        let decimals = ctx.accounts.token_mint.decimals;
+
+        if usd_value == 0 {
+            return err!(CustomErrorCode::InvalidAmount);
+        }
+
+        if ctx.accounts.campaign.campaign_status != CampaignStatus::Open {
+            return err!(CustomErrorCode::InvalidCampaignStatus);
+        }
+
+        let amount_offered = quote_usd_value(
+            &ctx.accounts.price_feed_config,
+            &ctx.accounts.price_feed,
+            usd_value,
+            decimals,
+        )?;
+        if amount_offered == 0 {
+            return err!(CustomErrorCode::InvalidAmount);
+        }
+
+        // The creator funds the escrow with the quoted amount; it is re-priced at settlement
+        let campaign = &mut ctx.accounts.campaign;
+        campaign.usd_value = usd_value;
+        campaign.amount_offered = amount_offered;
+        campaign.usd_settled_at = 0;
+
+        msg!(
+            "Campaign ID: {:?} priced at {} USD micro-units, {} tokens",
+            campaign.id,
+            usd_value,
+            amount_offered
+        );
+
+        Ok(())
+    }
+
+    pub fn set_campaign_vesting(
+        ctx: Context<SetCampaignVesting>,
+        vesting_cliff: i64,
+        vesting_duration: i64,
+    ) -> Result<()> {
+        if vesting_duration <= 0 || vesting_cliff < 0 || vesting_cliff > vesting_duration {
+            return err!(CustomErrorCode::InvalidTimeParameters);
+        }
+
+        if ctx.accounts.campaign.campaign_status != CampaignStatus::Open {
+            return err!(CustomErrorCode::InvalidCampaignStatus);
+        }
+
+        let campaign = &mut ctx.accounts.campaign;
+        campaign.vesting_cliff = vesting_cliff;
+        campaign.vesting_duration = vesting_duration;
+        ctx.accounts.vesting.campaign = campaign.key();
+        ctx.accounts.vesting.creator = ctx.accounts.creator.key();
+
+        msg!(
+            "Vesting set for campaign ID: {:?}: {} second cliff, {} second duration",
+            campaign.id,
+            vesting_cliff,
+            vesting_duration
+        );
+
+        Ok(())
+    }
+
+    pub fn claim_vested_tokens(ctx: Context<ClaimVestedTokens>) -> Result<()> {
+        let decimals = ctx.accounts.token_mint.decimals;
+
+        let current_time = Clock::get()?.unix_timestamp;
+        let vesting = &ctx.accounts.vesting;
+        let vested_amount = vesting.vested_amount(current_time);
+        let is_final_claim = vesting.start_ts != 0 && vested_amount == vesting.total_amount;
+        let mut claimable = vested_amount.checked_sub(vesting.claimed_amount).unwrap();
+        if claimable == 0 && !is_final_claim {
+            return err!(CustomErrorCode::NothingToClaim);
+        }
+
+        // The final claim sweeps whatever is left so the vault can be closed
+        if is_final_claim {
+            claimable = ctx.accounts.vesting_token_account.amount;
+        }
+
+        let campaign_key = vesting.campaign;
+        let seeds = &[b"vesting", campaign_key.as_ref(), &[ctx.bumps.vesting]];
         let signer_seeds = &[&seeds[..]];
 
-        token::transfer(
-            CpiContext::new_with_signer(
+        if claimable > 0 {
+            token_interface::transfer_checked(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    TransferChecked {
+                        from: ctx.accounts.vesting_token_account.to_account_info(),
+                        mint: ctx.accounts.token_mint.to_account_info(),
+                        to: ctx.accounts.kol_token_account.to_account_info(),
+                        authority: ctx.accounts.vesting.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                claimable,
+                decimals,
+            )?;
+        }
+
+        let vesting = &mut ctx.accounts.vesting;
+        vesting.claimed_amount = vesting.claimed_amount.checked_add(claimable).unwrap();
+
+        emit!(VestedTokensClaimed {
+            campaign: vesting.campaign,
+            kol: vesting.kol,
+            amount: claimable,
+            claimed_amount: vesting.claimed_amount,
+            total_amount: vesting.total_amount,
+        });
+
+        msg!(
+            "KOL {:?} claimed {} vested tokens ({} of {})",
+            vesting.kol,
+            claimable,
+            vesting.claimed_amount,
+            vesting.total_amount
+        );
+
+        if is_final_claim {
+            // The creator funded the vesting account and its vault, so the rent goes back to them
+            harvest_withheld_fees(
+                &ctx.accounts.vesting_token_account,
+                &ctx.accounts.token_mint,
+                &ctx.accounts.token_program,
+            )?;
+            token_interface::close_account(CpiContext::new_with_signer(
                 ctx.accounts.token_program.to_account_info(),
-                Transfer {
-                    from: ctx.accounts.campaign_token_account.to_account_info(),
-                    to: ctx.accounts.owner_token_account.to_account_info(),
-                    authority: ctx.accounts.open_campaign.to_account_info(),
+                CloseAccount {
+                    account: ctx.accounts.vesting_token_account.to_account_info(),
+                    destination: ctx.accounts.creator.to_account_info(),
+                    authority: ctx.accounts.vesting.to_account_info(),
                 },
                 signer_seeds,
-            ),
-            pool_amount,
+            ))?;
+            ctx.accounts
+                .vesting
+                .close(ctx.accounts.creator.to_account_info())?;
+        }
+
+        Ok(())
+    }
+
+    pub fn mark_campaign_unfulfilled(ctx: Context<MarkCampaignUnfulfilled>) -> Result<()> {
+        let decimals = ctx.accounts.token_mint.decimals;
+
+        // Anyone can crank an accepted campaign whose promotion window lapsed
+        // without a deliverable; the creator gets the whole escrow back.
+        if ctx.accounts.campaign.campaign_status != CampaignStatus::Accepted {
+            return err!(CustomErrorCode::InvalidCampaignStatus);
+        }
+
+        let current_time = Clock::get()?.unix_timestamp;
+        if current_time <= ctx.accounts.campaign.promotion_ends_in {
+            return err!(CustomErrorCode::InvalidTimeParameters);
+        }
+
+        let refund_amount = ctx.accounts.campaign_token_account.amount;
+        transfer_from_campaign(
+            &ctx.accounts.campaign,
+            ctx.bumps.campaign,
+            &ctx.accounts.campaign_token_account,
+            &ctx.accounts.creator_token_account,
+            &ctx.accounts.token_mint,
+            decimals,
+            &ctx.accounts.token_program,
+            refund_amount,
         )?;
 
+        ctx.accounts.campaign.campaign_status = CampaignStatus::Unfulfilled;
+
+        emit!(CampaignUnfulfilled {
+            campaign_id: ctx.accounts.campaign.id,
+            selected_kol: ctx.accounts.campaign.selected_kol,
+            refund_amount,
+            marked_by: ctx.accounts.caller.key(),
+            marked_at: current_time,
+        });
+
         msg!(
-            "Open campaign completed with ID: {:?}, status: {:?}",
-            ctx.accounts.open_campaign.id,
-            ctx.accounts.open_campaign.campaign_status
+            "Campaign ID: {:?} marked unfulfilled. Refunded {} to creator",
+            ctx.accounts.campaign.id,
+            refund_amount
         );
 
         Ok(())
     }
 
-    pub fn add_allowed_token(
-        ctx: Context<UpdateAllowedTokens>,
-        token_mint: Pubkey,
-        token_decimal: u8,
-    ) -> Result<()> {
-        // Check if the token is already allowed
-        if ctx
-            .accounts
-            .marketplace_state
-            .allowed_tokens
-            .contains(&token_mint)
-        {
-            return err!(CustomErrorCode::TokenAlreadyAllowed);
+    pub fn settle_campaign_bond(ctx: Context<SettleCampaignBond>) -> Result<()> {
+        let decimals = ctx.accounts.token_mint.decimals;
+
+        let campaign = &ctx.accounts.campaign;
+        if campaign.bond_amount == 0 || campaign.bond_settled {
+            return err!(CustomErrorCode::NoBond);
         }
 
-        // Check if we're not exceeding the maximum allowed tokens (20 as per space allocation)
-        require!(
-            ctx.accounts.marketplace_state.allowed_tokens.len() < 20,
-            CustomErrorCode::TooManyTokens
+        // The bond goes back to the KOL unless they failed to deliver; a
+        // resolved dispute slashes it by the creator's share of the ruling.
+        let bond_balance = ctx.accounts.bond_token_account.amount;
+        let kol_amount = match campaign.campaign_status {
+            CampaignStatus::Fulfilled | CampaignStatus::Discarded => bond_balance,
+            CampaignStatus::Resolved => match &ctx.accounts.dispute {
+                Some(dispute) => {
+                    let total = dispute.kol_amount as u128 + dispute.creator_amount as u128;
+                    (bond_balance as u128 * dispute.kol_amount as u128)
+                        .checked_div(total)
+                        .unwrap_or(0) as u64
+                }
+                None => return err!(CustomErrorCode::InvalidParameters),
+            },
+            CampaignStatus::Unfulfilled => 0,
+            _ => return err!(CustomErrorCode::InvalidCampaignStatus),
+        };
+        let slashed_amount = bond_balance - kol_amount;
+        let owner_amount = slashed_amount
+            .checked_mul(ctx.accounts.marketplace_state.bond_slash_treasury_bps as u64)
+            .unwrap()
+            .checked_div(DIVIDER)
+            .unwrap();
+        let creator_amount = slashed_amount - owner_amount;
+
+        let campaign_key = campaign.key();
+        let seeds = &[b"bond", campaign_key.as_ref(), &[ctx.bumps.bond_authority]];
+        let signer_seeds = &[&seeds[..]];
+
+        for (to, amount) in [
+            (&ctx.accounts.kol_token_account, kol_amount),
+            (&ctx.accounts.owner_token_account, owner_amount),
+            (&ctx.accounts.creator_token_account, creator_amount),
+        ] {
+            if amount == 0 {
+                continue;
+            }
+
+            token_interface::transfer_checked(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    TransferChecked {
+                        from: ctx.accounts.bond_token_account.to_account_info(),
+                        mint: ctx.accounts.token_mint.to_account_info(),
+                        to: to.to_account_info(),
+                        authority: ctx.accounts.bond_authority.to_account_info(),
+                    },
+                    signer_seeds,
+                ),
+                amount,
+                decimals,
+            )?;
+        }
+
+        // The KOL funded the bond vault, so its rent goes back to them
+        harvest_withheld_fees(
+            &ctx.accounts.bond_token_account,
+            &ctx.accounts.token_mint,
+            &ctx.accounts.token_program,
+        )?;
+        token_interface::close_account(CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: ctx.accounts.bond_token_account.to_account_info(),
+                destination: ctx.accounts.kol.to_account_info(),
+                authority: ctx.accounts.bond_authority.to_account_info(),
+            },
+            signer_seeds,
+        ))?;
+
+        ctx.accounts.campaign.bond_settled = true;
+
+        emit!(BondSettled {
+            campaign_id: ctx.accounts.campaign.id,
+            kol: ctx.accounts.campaign.selected_kol,
+            returned_amount: kol_amount,
+            slashed_to_creator: creator_amount,
+            slashed_to_treasury: owner_amount,
+        });
+
+        msg!(
+            "Settled bond of campaign ID: {:?}: {} returned to KOL, {} to creator, {} to owner",
+            ctx.accounts.campaign.id,
+            kol_amount,
+            creator_amount,
+            owner_amount
         );
 
-        // Add the new token and its decimal
-        ctx.accounts
-            .marketplace_state
-            .allowed_tokens
-            .push(token_mint);
-        ctx.accounts
-            .marketplace_state
-            .token_decimals
-            .push(token_decimal);
+        Ok(())
+    }
+
+    pub fn set_campaign_referrer(
+        ctx: Context<SetCampaignReferrer>,
+        referrer: Pubkey,
+        referrer_share_bps: u16,
+    ) -> Result<()> {
+        if referrer_share_bps > ctx.accounts.marketplace_state.max_referrer_share_bps {
+            return err!(CustomErrorCode::RevenueShareTooHigh);
+        }
+
+        let campaign = &mut ctx.accounts.campaign;
+        if campaign.campaign_status != CampaignStatus::Open {
+            return err!(CustomErrorCode::InvalidCampaignStatus);
+        }
+
+        campaign.referrer = referrer;
+        campaign.referrer_share_bps = referrer_share_bps;
+
+        msg!(
+            "Referrer {:?} set for campaign ID: {:?} with {} bps of the platform fee",
+            referrer,
+            campaign.id,
+            referrer_share_bps
+        );
 
-        msg!("Added new allowed token: {}", token_mint);
         Ok(())
     }
 
-    pub fn remove_allowed_token(
-        ctx: Context<UpdateAllowedTokens>,
-        token_mint: Pubkey,
+    pub fn set_campaign_agency(
+        ctx: Context<SetCampaignAgency>,
+        agency: Pubkey,
+        agency_share_bps: u16,
     ) -> Result<()> {
-        let marketplace_state = &mut ctx.accounts.marketplace_state;
+        if agency_share_bps > ctx.accounts.marketplace_state.max_agency_share_bps {
+            return err!(CustomErrorCode::RevenueShareTooHigh);
+        }
+
+        // The agency's cut comes out of the KOL share, so only the KOL can set
+        // it, and only before the deliverable is submitted
+        let campaign = &mut ctx.accounts.campaign;
+        if campaign.campaign_status != CampaignStatus::Open
+            && campaign.campaign_status != CampaignStatus::Accepted
+        {
+            return err!(CustomErrorCode::InvalidCampaignStatus);
+        }
+
+        campaign.agency = agency;
+        campaign.agency_share_bps = agency_share_bps;
+
+        msg!(
+            "Agency {:?} set for campaign ID: {:?} with {} bps of the KOL share",
+            agency,
+            campaign.id,
+            agency_share_bps
+        );
+
+        Ok(())
+    }
+
+    pub fn add_campaign_bundle_leg(ctx: Context<AddCampaignBundleLeg>, amount: u64) -> Result<()> {
+        if amount == 0 {
+            return err!(CustomErrorCode::InvalidAmount);
+        }
+
+        let campaign = &ctx.accounts.campaign;
+        let token_mint = ctx.accounts.token_mint.key();
+
+        // Legs can only be added before the KOL commits to the offer
+        if campaign.campaign_status != CampaignStatus::Open {
+            return err!(CustomErrorCode::InvalidCampaignStatus);
+        }
+
+        if campaign.bundle_legs.len() >= MAX_BUNDLE_LEGS {
+            return err!(CustomErrorCode::TooManyBundleLegs);
+        }
+
+        if token_mint == campaign.token_mint
+            || campaign
+                .bundle_legs
+                .iter()
+                .any(|leg| leg.token_mint == token_mint)
+        {
+            return err!(CustomErrorCode::InvalidParameters);
+        }
+
+        check_mint_extensions(&ctx.accounts.token_mint)?;
+        let decimals = ctx.accounts.token_mint.decimals;
+
+        // Record what the vault actually received, net of any transfer fee
+        let balance_before = ctx.accounts.leg_token_account.amount;
+        token_interface::transfer_checked(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.creator_token_account.to_account_info(),
+                    mint: ctx.accounts.token_mint.to_account_info(),
+                    to: ctx.accounts.leg_token_account.to_account_info(),
+                    authority: ctx.accounts.creator.to_account_info(),
+                },
+            ),
+            amount,
+            decimals,
+        )?;
+        ctx.accounts.leg_token_account.reload()?;
+        let received = ctx.accounts.leg_token_account.amount - balance_before;
+
+        let campaign = &mut ctx.accounts.campaign;
+        campaign.bundle_legs.push(BundleLeg {
+            token_mint,
+            amount: received,
+            settled: false,
+        });
+
+        msg!(
+            "Added bundle leg of {} {:?} to campaign ID: {:?}",
+            received,
+            token_mint,
+            campaign.id
+        );
+
+        Ok(())
+    }
+
+    pub fn settle_campaign_bundle_leg(
+        ctx: Context<SettleCampaignBundleLeg>,
+        leg_index: u8,
+    ) -> Result<()> {
+        let campaign = &ctx.accounts.campaign;
+        let leg = match campaign.bundle_legs.get(leg_index as usize) {
+            Some(leg) => *leg,
+            None => return err!(CustomErrorCode::InvalidParameters),
+        };
+
+        if leg.settled {
+            return err!(CustomErrorCode::BundleLegSettled);
+        }
+
+        if ctx.accounts.token_mint.key() != leg.token_mint {
+            return err!(CustomErrorCode::InvalidParameters);
+        }
+
+        let decimals = ctx.accounts.token_mint.decimals;
+
+        // Legs of a fulfilled campaign are paid out together with the main
+        // escrow. The rest follow the arbiter's ratio on a resolved dispute, or
+        // go back to the creator in full. Kill fees and vesting only apply to the
+        // main token.
+        let leg_balance = ctx.accounts.leg_token_account.amount;
+        let kol_gross_amount = match campaign.campaign_status {
+            CampaignStatus::Resolved => match &ctx.accounts.dispute {
+                Some(dispute) => {
+                    let total = dispute.kol_amount as u128 + dispute.creator_amount as u128;
+                    (leg_balance as u128 * dispute.kol_amount as u128)
+                        .checked_div(total)
+                        .unwrap_or(0) as u64
+                }
+                None => return err!(CustomErrorCode::InvalidParameters),
+            },
+            CampaignStatus::Discarded | CampaignStatus::Unfulfilled => 0,
+            _ => return err!(CustomErrorCode::InvalidCampaignStatus),
+        };
+        let creator_amount = leg_balance - kol_gross_amount;
+
+        let bump = ctx.bumps.campaign;
+        let (kol_amount, owner_amount) = if kol_gross_amount > 0 {
+            let fee_bps = ctx
+                .accounts
+                .marketplace_state
+                .platform_fee_bps(&ctx.accounts.creator_profile, &leg.token_mint);
+            pay_out_campaign(
+                campaign,
+                bump,
+                &ctx.accounts.leg_token_account,
+                &ctx.accounts.kol_token_account,
+                &ctx.accounts.owner_token_account,
+                ctx.accounts.referrer_token_account.as_ref(),
+                ctx.accounts.agency_token_account.as_ref(),
+                kol_gross_amount,
+                fee_bps,
+                &ctx.accounts.token_mint,
+                decimals,
+                &ctx.accounts.token_program,
+            )?
+        } else {
+            (0, 0)
+        };
+        transfer_from_campaign(
+            campaign,
+            bump,
+            &ctx.accounts.leg_token_account,
+            &ctx.accounts.creator_token_account,
+            &ctx.accounts.token_mint,
+            decimals,
+            &ctx.accounts.token_program,
+            creator_amount,
+        )?;
+
+        // The emptied leg vault's rent goes back to the creator
+        let seeds = &[
+            b"campaign",
+            campaign.creator_address.as_ref(),
+            &campaign.counter.to_le_bytes(),
+            &[bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+        harvest_withheld_fees(
+            &ctx.accounts.leg_token_account,
+            &ctx.accounts.token_mint,
+            &ctx.accounts.token_program,
+        )?;
+        token_interface::close_account(CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: ctx.accounts.leg_token_account.to_account_info(),
+                destination: ctx.accounts.creator.to_account_info(),
+                authority: campaign.to_account_info(),
+            },
+            signer_seeds,
+        ))?;
+
+        ctx.accounts.campaign.bundle_legs[leg_index as usize].settled = true;
+        if kol_gross_amount > 0 {
+            ctx.accounts.creator_profile.record_volume(
+                &ctx.accounts.campaign.creator_address,
+                &leg.token_mint,
+                kol_gross_amount,
+            );
+        }
+
+        msg!(
+            "Settled bundle leg {} of campaign ID: {:?}: {} to KOL, {} to owner, {} to creator",
+            leg_index,
+            ctx.accounts.campaign.id,
+            kol_amount,
+            owner_amount,
+            creator_amount
+        );
+
+        Ok(())
+    }
+
+    pub fn create_native_campaign(
+        ctx: Context<CreateNativeCampaign>,
+        selected_kol: Pubkey,
+        offering_amount: u64,
+        promotion_ends_in: i64,
+        offer_ends_in: i64,
+        kill_fee_bps: u16,
+    ) -> Result<()> {
+        if offering_amount == 0 {
+            return err!(CustomErrorCode::InvalidAmount);
+        }
+
+        if kill_fee_bps as u64 > DIVIDER {
+            return err!(CustomErrorCode::InvalidParameters);
+        }
+
+        let current_time = Clock::get()?.unix_timestamp;
+        if offer_ends_in <= current_time || promotion_ends_in <= current_time {
+            return err!(CustomErrorCode::InvalidTimeParameters);
+        }
+
+        // Generate campaign ID similar to SPL campaigns
+        let creator_key = ctx.accounts.creator.key();
+        let counter = ctx.accounts.marketplace_state.campaign_counter;
+
+        let mut data_to_hash = vec![];
+        data_to_hash.extend_from_slice(&current_time.to_le_bytes());
+        data_to_hash.extend_from_slice(creator_key.as_ref());
+        data_to_hash.extend_from_slice(&counter.to_le_bytes());
+
+        let hashed = hash(&data_to_hash).to_bytes();
+        let id_data = [hashed[0], hashed[1], hashed[2], hashed[3]];
+
+        // Increment the counter
+        ctx.accounts.marketplace_state.campaign_counter = ctx
+            .accounts
+            .marketplace_state
+            .campaign_counter
+            .checked_add(1)
+            .unwrap();
+
+        // Escrow the offer in the campaign PDA itself, on top of its rent
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.creator.to_account_info(),
+                    to: ctx.accounts.campaign.to_account_info(),
+                },
+            ),
+            offering_amount,
+        )?;
+
+        let campaign = &mut ctx.accounts.campaign;
+        campaign.id = id_data;
+        campaign.counter = counter;
+        campaign.created_at = current_time;
+        campaign.creator_address = creator_key;
+        campaign.token_mint = Pubkey::default();
+        campaign.selected_kol = selected_kol;
+        campaign.offer_ends_in = offer_ends_in;
+        campaign.promotion_ends_in = promotion_ends_in;
+        campaign.amount_offered = offering_amount;
+        campaign.campaign_status = CampaignStatus::Open;
+        campaign.kill_fee_bps = kill_fee_bps;
+        campaign.is_native = true;
+
+        msg!(
+            "Native campaign created with ID: {:?}, creator: {:?} and counter: {:?}",
+            id_data,
+            creator_key,
+            counter
+        );
+
+        Ok(())
+    }
+
+    pub fn discard_native_campaign(ctx: Context<DiscardNativeCampaign>) -> Result<()> {
+        let campaign_balance = native_escrow_balance(&ctx.accounts.campaign)?;
+
+        // Same kill fee rules as SPL campaigns
+        let kill_fee = match ctx.accounts.campaign.campaign_status {
+            CampaignStatus::Open => 0,
+            CampaignStatus::Accepted => ctx
+                .accounts
+                .campaign
+                .amount_offered
+                .checked_mul(ctx.accounts.campaign.kill_fee_bps as u64)
+                .unwrap()
+                .checked_div(DIVIDER)
+                .unwrap()
+                .min(campaign_balance),
+            _ => return err!(CustomErrorCode::InvalidCampaignStatus),
+        };
+        let refund_amount = campaign_balance - kill_fee;
+
+        transfer_lamports_from_campaign(
+            &ctx.accounts.campaign,
+            &ctx.accounts.kol.to_account_info(),
+            kill_fee,
+        )?;
+        transfer_lamports_from_campaign(
+            &ctx.accounts.campaign,
+            &ctx.accounts.creator.to_account_info(),
+            refund_amount,
+        )?;
+
+        ctx.accounts.campaign.campaign_status = CampaignStatus::Discarded;
+
+        msg!(
+            "Transferred {} lamports back to creator and {} kill fee to KOL",
+            refund_amount,
+            kill_fee
+        );
+
+        Ok(())
+    }
+
+    pub fn fulfil_native_campaign(ctx: Context<FulfilNativeCampaign>) -> Result<()> {
+        let campaign_status = ctx.accounts.campaign.campaign_status;
+        if campaign_status != CampaignStatus::Accepted
+            && campaign_status != CampaignStatus::Submitted
+        {
+            return err!(CustomErrorCode::InvalidCampaignStatus);
+        }
+
+        let (kol_amount, owner_amount) = settle_native_campaign(
+            &ctx.accounts.marketplace_state,
+            &mut ctx.accounts.campaign,
+            &mut ctx.accounts.creator_profile,
+            &ctx.accounts.kol.to_account_info(),
+            &ctx.accounts.owner.to_account_info(),
+            ctx.accounts.referrer.as_ref(),
+            ctx.accounts.agency.as_ref(),
+        )?;
+
+        msg!(
+            "Native campaign fulfilled with ID: {:?}. Transferred {} lamports to KOL and {} to owner",
+            ctx.accounts.campaign.id,
+            kol_amount,
+            owner_amount
+        );
+
+        Ok(())
+    }
+
+    pub fn approve_native_deliverable(ctx: Context<ApproveNativeDeliverable>) -> Result<()> {
+        if ctx.accounts.campaign.campaign_status != CampaignStatus::Submitted {
+            return err!(CustomErrorCode::InvalidCampaignStatus);
+        }
+
+        let (kol_amount, owner_amount) = settle_native_campaign(
+            &ctx.accounts.marketplace_state,
+            &mut ctx.accounts.campaign,
+            &mut ctx.accounts.creator_profile,
+            &ctx.accounts.kol.to_account_info(),
+            &ctx.accounts.owner.to_account_info(),
+            ctx.accounts.referrer.as_ref(),
+            ctx.accounts.agency.as_ref(),
+        )?;
+
+        msg!(
+            "Deliverable approved for native campaign ID: {:?}. Transferred {} lamports to KOL and {} to owner",
+            ctx.accounts.campaign.id,
+            kol_amount,
+            owner_amount
+        );
+
+        Ok(())
+    }
+
+    pub fn release_native_payout(ctx: Context<ReleaseNativePayout>) -> Result<()> {
+        if ctx.accounts.campaign.campaign_status != CampaignStatus::Submitted {
+            return err!(CustomErrorCode::InvalidCampaignStatus);
+        }
+
+        let current_time = Clock::get()?.unix_timestamp;
+        if current_time < ctx.accounts.campaign.review_ends_in {
+            return err!(CustomErrorCode::ReviewWindowActive);
+        }
+
+        let (kol_amount, owner_amount) = settle_native_campaign(
+            &ctx.accounts.marketplace_state,
+            &mut ctx.accounts.campaign,
+            &mut ctx.accounts.creator_profile,
+            &ctx.accounts.kol.to_account_info(),
+            &ctx.accounts.owner.to_account_info(),
+            ctx.accounts.referrer.as_ref(),
+            ctx.accounts.agency.as_ref(),
+        )?;
+
+        msg!(
+            "Payout released for native campaign ID: {:?} by {:?}. Transferred {} lamports to KOL and {} to owner",
+            ctx.accounts.campaign.id,
+            ctx.accounts.caller.key(),
+            kol_amount,
+            owner_amount
+        );
+
+        Ok(())
+    }
+
+    pub fn resolve_native_dispute(
+        ctx: Context<ResolveNativeDispute>,
+        kol_amount: u64,
+    ) -> Result<()> {
+        if ctx.accounts.campaign.campaign_status != CampaignStatus::Disputed {
+            return err!(CustomErrorCode::InvalidCampaignStatus);
+        }
+
+        let campaign_balance = native_escrow_balance(&ctx.accounts.campaign)?;
+        if kol_amount > campaign_balance {
+            return err!(CustomErrorCode::InvalidAmount);
+        }
+        let creator_amount = campaign_balance - kol_amount;
+
+        // Platform fee only applies to the KOL portion
+        let fee_bps = ctx.accounts.marketplace_state.platform_fee_bps(
+            &ctx.accounts.creator_profile,
+            &ctx.accounts.campaign.token_mint,
+        );
+        let (kol_net_amount, owner_amount) = pay_out_native_campaign(
+            &ctx.accounts.campaign,
+            &ctx.accounts.kol.to_account_info(),
+            &ctx.accounts.owner.to_account_info(),
+            ctx.accounts.referrer.as_ref(),
+            ctx.accounts.agency.as_ref(),
+            kol_amount,
+            fee_bps,
+        )?;
+        transfer_lamports_from_campaign(
+            &ctx.accounts.campaign,
+            &ctx.accounts.creator.to_account_info(),
+            creator_amount,
+        )?;
+        ctx.accounts
+            .creator_profile
+            .record_settlement(&ctx.accounts.campaign, kol_amount);
+
+        ctx.accounts.campaign.campaign_status = CampaignStatus::Resolved;
+
+        let dispute = &mut ctx.accounts.dispute;
+        dispute.resolved_at = Clock::get()?.unix_timestamp;
+        dispute.kol_amount = kol_amount;
+        dispute.creator_amount = creator_amount;
+
+        emit!(DisputeResolved {
+            campaign_id: ctx.accounts.campaign.id,
+            kol_amount,
+            creator_amount,
+        });
+
+        msg!(
+            "Dispute resolved for native campaign ID: {:?}. Transferred {} lamports to KOL, {} to owner and {} to creator",
+            ctx.accounts.campaign.id,
+            kol_net_amount,
+            owner_amount,
+            creator_amount
+        );
+
+        Ok(())
+    }
+
+    pub fn mark_native_campaign_unfulfilled(
+        ctx: Context<MarkNativeCampaignUnfulfilled>,
+    ) -> Result<()> {
+        // Same crank as mark_campaign_unfulfilled, refunding the lamport escrow
+        if ctx.accounts.campaign.campaign_status != CampaignStatus::Accepted {
+            return err!(CustomErrorCode::InvalidCampaignStatus);
+        }
+
+        let current_time = Clock::get()?.unix_timestamp;
+        if current_time <= ctx.accounts.campaign.promotion_ends_in {
+            return err!(CustomErrorCode::InvalidTimeParameters);
+        }
+
+        let refund_amount = native_escrow_balance(&ctx.accounts.campaign)?;
+        transfer_lamports_from_campaign(
+            &ctx.accounts.campaign,
+            &ctx.accounts.creator.to_account_info(),
+            refund_amount,
+        )?;
+
+        ctx.accounts.campaign.campaign_status = CampaignStatus::Unfulfilled;
+
+        emit!(CampaignUnfulfilled {
+            campaign_id: ctx.accounts.campaign.id,
+            selected_kol: ctx.accounts.campaign.selected_kol,
+            refund_amount,
+            marked_by: ctx.accounts.caller.key(),
+            marked_at: current_time,
+        });
+
+        msg!(
+            "Native campaign ID: {:?} marked unfulfilled. Refunded {} lamports to creator",
+            ctx.accounts.campaign.id,
+            refund_amount
+        );
+
+        Ok(())
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_open_campaign(
+        ctx: Context<CreateOpenCampaign>,
+        promotion_ends_in: i64,
+        pool_amount: u64,
+        max_participants: u32,
+        payout_mode: OpenCampaignPayoutMode,
+        eligibility: EligibilityConfig,
+        reward_per_submission: u64,
+        max_payout_per_kol: u64,
+    ) -> Result<()> {
+        if pool_amount == 0 {
+            return err!(CustomErrorCode::InvalidAmount);
+        }
+
+        let current_time = Clock::get()?.unix_timestamp;
+        if promotion_ends_in <= current_time {
+            return err!(CustomErrorCode::InvalidTimeParameters);
+        }
+
+        check_mint_extensions(&ctx.accounts.token_mint)?;
+
+        // Escrow accounting tracks what the vault holds once funded
+        let pool_amount = net_of_transfer_fee(&ctx.accounts.token_mint, pool_amount)?;
+
+        // Bounties are paid as allocations, so they need allocation mode and
+        // must fit both the pool and the per-KOL cap
+        if reward_per_submission > 0
+            && (payout_mode != OpenCampaignPayoutMode::Allocation
+                || reward_per_submission > pool_amount
+                || (max_payout_per_kol > 0 && reward_per_submission > max_payout_per_kol))
+        {
+            return err!(CustomErrorCode::InvalidParameters);
+        }
+
+        // Generate campaign ID similar to regular campaigns
+        let creator_key = ctx.accounts.creator.key();
+        let counter = ctx.accounts.marketplace_state.campaign_counter;
+
+        let mut data_to_hash = vec![];
+        data_to_hash.extend_from_slice(&current_time.to_le_bytes());
+        data_to_hash.extend_from_slice(creator_key.as_ref());
+        data_to_hash.extend_from_slice(&counter.to_le_bytes());
+
+        let hashed = hash(&data_to_hash).to_bytes();
+        let id_data = [hashed[0], hashed[1], hashed[2], hashed[3]];
+
+        // Increment the counter
+        ctx.accounts.marketplace_state.campaign_counter = ctx
+            .accounts
+            .marketplace_state
+            .campaign_counter
+            .checked_add(1)
+            .unwrap();
+
+        let campaign = &mut ctx.accounts.open_campaign;
+        campaign.id = id_data;
+        campaign.counter = counter;
+        campaign.created_at = current_time;
+        campaign.creator_address = ctx.accounts.creator.key();
+        campaign.token_mint = ctx.accounts.token_mint.key();
+        campaign.promotion_ends_in = promotion_ends_in;
+        campaign.pool_amount = pool_amount;
+        campaign.campaign_status = OpenCampaignStatus::Published;
+        campaign.max_participants = max_participants;
+        campaign.participant_count = 0;
+        campaign.payout_mode = payout_mode;
+        // Fixed at creation so criteria and terms can't change under KOLs about to join
+        campaign.eligibility = eligibility;
+        campaign.reward_per_submission = reward_per_submission;
+        campaign.max_payout_per_kol = max_payout_per_kol;
+
+        msg!(
+            "Open campaign created with ID: {:?}, creator: {:?} and counter: {:?}",
+            id_data,
+            ctx.accounts.creator.key(),
+            counter
+        );
+
+        Ok(())
+    }
+
+    pub fn top_up_open_campaign(ctx: Context<TopUpOpenCampaign>, amount: u64) -> Result<()> {
+        let decimals = ctx.accounts.token_mint.decimals;
+
+        if amount == 0 {
+            return err!(CustomErrorCode::InvalidAmount);
+        }
+
+        let current_time = Clock::get()?.unix_timestamp;
+        if ctx.accounts.open_campaign.campaign_status != OpenCampaignStatus::Published {
+            return err!(CustomErrorCode::InvalidOpenCampaignStatus);
+        }
+
+        if current_time > ctx.accounts.open_campaign.promotion_ends_in {
+            return err!(CustomErrorCode::CampaignExpired);
+        }
+
+        // Credit what the vault actually received, net of any transfer fee
+        let balance_before = ctx.accounts.campaign_token_account.amount;
+        token_interface::transfer_checked(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.creator_token_account.to_account_info(),
+                    mint: ctx.accounts.token_mint.to_account_info(),
+                    to: ctx.accounts.campaign_token_account.to_account_info(),
+                    authority: ctx.accounts.creator.to_account_info(),
+                },
+            ),
+            amount,
+            decimals,
+        )?;
+        ctx.accounts.campaign_token_account.reload()?;
+        let received = ctx.accounts.campaign_token_account.amount - balance_before;
+
+        let open_campaign = &mut ctx.accounts.open_campaign;
+        open_campaign.pool_amount = open_campaign.pool_amount.checked_add(received).unwrap();
+
+        msg!(
+            "Open campaign ID: {:?} topped up by {}, pool is now {}",
+            open_campaign.id,
+            received,
+            open_campaign.pool_amount
+        );
+
+        Ok(())
+    }
+
+    pub fn extend_open_campaign(
+        ctx: Context<ExtendOpenCampaign>,
+        promotion_ends_in: i64,
+    ) -> Result<()> {
+        let open_campaign = &mut ctx.accounts.open_campaign;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        if open_campaign.campaign_status != OpenCampaignStatus::Published {
+            return err!(CustomErrorCode::InvalidOpenCampaignStatus);
+        }
+
+        if current_time > open_campaign.promotion_ends_in {
+            return err!(CustomErrorCode::CampaignExpired);
+        }
+
+        if promotion_ends_in <= open_campaign.promotion_ends_in {
+            return err!(CustomErrorCode::InvalidTimeParameters);
+        }
+
+        // Extensions are capped cumulatively so repeated calls can't bypass the limit
+        let extended_by = open_campaign
+            .extended_by
+            .checked_add(promotion_ends_in - open_campaign.promotion_ends_in)
+            .unwrap();
+        if extended_by > ctx.accounts.marketplace_state.max_deadline_extension {
+            return err!(CustomErrorCode::ExtensionTooLong);
+        }
+
+        open_campaign.extended_by = extended_by;
+        open_campaign.promotion_ends_in = promotion_ends_in;
+
+        msg!(
+            "Open campaign ID: {:?} extended to {}",
+            open_campaign.id,
+            promotion_ends_in
+        );
+
+        Ok(())
+    }
+
+    pub fn submit_open_campaign_entry(
+        ctx: Context<SubmitOpenCampaignEntry>,
+        content_hash: [u8; 32],
+    ) -> Result<()> {
+        let open_campaign = &ctx.accounts.open_campaign;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        if open_campaign.campaign_status != OpenCampaignStatus::Published {
+            return err!(CustomErrorCode::InvalidOpenCampaignStatus);
+        }
+
+        if current_time > open_campaign.promotion_ends_in {
+            return err!(CustomErrorCode::CampaignExpired);
+        }
+
+        if content_hash == [0u8; 32] {
+            return err!(CustomErrorCode::InvalidParameters);
+        }
+
+        let participation = &mut ctx.accounts.participation;
+        participation.submission_hash = content_hash;
+        participation.submitted_at = current_time;
+
+        msg!(
+            "KOL {:?} submitted an entry to open campaign ID: {:?}",
+            ctx.accounts.kol.key(),
+            open_campaign.id
+        );
+
+        Ok(())
+    }
+
+    pub fn accept_open_campaign_submission(
+        ctx: Context<AcceptOpenCampaignSubmission>,
+    ) -> Result<()> {
+        let open_campaign = &mut ctx.accounts.open_campaign;
+        let participation = &mut ctx.accounts.participation;
+
+        if open_campaign.campaign_status != OpenCampaignStatus::Published {
+            return err!(CustomErrorCode::InvalidOpenCampaignStatus);
+        }
+
+        if open_campaign.reward_per_submission == 0 {
+            return err!(CustomErrorCode::InvalidPayoutMode);
+        }
+
+        if participation.submission_hash == [0u8; 32] {
+            return err!(CustomErrorCode::NoPendingSubmission);
+        }
+
+        let allocation = participation
+            .allocation
+            .checked_add(open_campaign.reward_per_submission)
+            .unwrap();
+        if open_campaign.capped_payout(allocation) < allocation {
+            return err!(CustomErrorCode::PayoutCapExceeded);
+        }
+
+        // Once the pool is fully reserved the bounty is over
+        let allocated_amount = open_campaign
+            .allocated_amount
+            .checked_add(open_campaign.reward_per_submission)
+            .unwrap();
+        if allocated_amount > open_campaign.pool_amount {
+            return err!(CustomErrorCode::AllocationExceedsPool);
+        }
+
+        open_campaign.allocated_amount = allocated_amount;
+        open_campaign.accepted_submissions =
+            open_campaign.accepted_submissions.checked_add(1).unwrap();
+        participation.allocation = allocation;
+        // Clear the slot so each reward needs a fresh submission
+        participation.submission_hash = [0u8; 32];
+
+        msg!(
+            "Accepted submission from KOL {:?} in open campaign ID: {:?}, allocation is now {}",
+            participation.kol,
+            open_campaign.id,
+            allocation
+        );
+
+        Ok(())
+    }
+
+    pub fn set_kol_reputation(
+        ctx: Context<SetKolReputation>,
+        kol: Pubkey,
+        score: u64,
+    ) -> Result<()> {
+        let kol_reputation = &mut ctx.accounts.kol_reputation;
+        kol_reputation.kol = kol;
+        kol_reputation.score = score;
+        kol_reputation.updated_at = Clock::get()?.unix_timestamp;
+
+        msg!("Set reputation of KOL {:?} to {}", kol, score);
+
+        Ok(())
+    }
+
+    pub fn join_open_campaign(
+        ctx: Context<JoinOpenCampaign>,
+        allowlist_proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        ctx.accounts.open_campaign.eligibility.check(
+            &ctx.accounts.kol.key(),
+            &allowlist_proof,
+            ctx.accounts.kol_reputation.as_deref(),
+            ctx.accounts.eligibility_token_account.as_deref(),
+        )?;
+
+        let open_campaign = &mut ctx.accounts.open_campaign;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        if open_campaign.campaign_status != OpenCampaignStatus::Published {
+            return err!(CustomErrorCode::InvalidOpenCampaignStatus);
+        }
+
+        if current_time > open_campaign.promotion_ends_in {
+            return err!(CustomErrorCode::CampaignExpired);
+        }
+
+        if open_campaign.max_participants > 0
+            && open_campaign.participant_count >= open_campaign.max_participants
+        {
+            return err!(CustomErrorCode::ParticipantLimitReached);
+        }
+
+        open_campaign.participant_count = open_campaign.participant_count.checked_add(1).unwrap();
+
+        let participation = &mut ctx.accounts.participation;
+        participation.open_campaign = open_campaign.key();
+        participation.kol = ctx.accounts.kol.key();
+        participation.joined_at = current_time;
+
+        msg!(
+            "KOL {:?} joined open campaign ID: {:?}",
+            ctx.accounts.kol.key(),
+            open_campaign.id
+        );
+
+        Ok(())
+    }
+
+    pub fn leave_open_campaign(ctx: Context<LeaveOpenCampaign>) -> Result<()> {
+        let open_campaign = &mut ctx.accounts.open_campaign;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        if open_campaign.campaign_status != OpenCampaignStatus::Published {
+            return err!(CustomErrorCode::InvalidOpenCampaignStatus);
+        }
+
+        if current_time >= open_campaign.promotion_ends_in {
+            return err!(CustomErrorCode::CampaignExpired);
+        }
+
+        open_campaign.participant_count = open_campaign.participant_count.checked_sub(1).unwrap();
+        open_campaign.allocated_amount = open_campaign
+            .allocated_amount
+            .checked_sub(ctx.accounts.participation.allocation)
+            .unwrap();
+        if ctx.accounts.participation.engagement_score > 0 {
+            open_campaign.total_engagement_score = open_campaign
+                .total_engagement_score
+                .checked_sub(ctx.accounts.participation.engagement_score)
+                .unwrap();
+            open_campaign.scored_participants =
+                open_campaign.scored_participants.checked_sub(1).unwrap();
+        }
+
+        msg!(
+            "KOL {:?} left open campaign ID: {:?}",
+            ctx.accounts.kol.key(),
+            open_campaign.id
+        );
+
+        Ok(())
+    }
+
+    pub fn close_participation(ctx: Context<CloseParticipation>) -> Result<()> {
+        // A closed open campaign has nothing left to claim
+        let open_campaign_info = ctx.accounts.open_campaign.to_account_info();
+        if *open_campaign_info.owner == ID {
+            let open_campaign =
+                OpenCampaign::try_deserialize(&mut &open_campaign_info.try_borrow_data()?[..])?;
+            let participation = &ctx.accounts.participation;
+
+            match open_campaign.campaign_status {
+                // Live campaigns are left through `leave_open_campaign`
+                OpenCampaignStatus::Published => {
+                    return err!(CustomErrorCode::InvalidOpenCampaignStatus);
+                }
+                // Don't let a KOL throw away a payout they can still claim
+                OpenCampaignStatus::Fulfilled => {
+                    let has_claim = !participation.claimed
+                        && (participation.allocation > 0 || participation.engagement_score > 0);
+                    if has_claim && Clock::get()?.unix_timestamp <= open_campaign.claims_end_at {
+                        return err!(CustomErrorCode::ClaimsOutstanding);
+                    }
+                }
+                OpenCampaignStatus::Discarded => {}
+            }
+        }
+
+        msg!(
+            "KOL {:?} closed participation in open campaign {:?}",
+            ctx.accounts.kol.key(),
+            ctx.accounts.open_campaign.key()
+        );
+
+        Ok(())
+    }
+
+    pub fn set_open_campaign_allocation(
+        ctx: Context<SetOpenCampaignAllocation>,
+        amount: u64,
+    ) -> Result<()> {
+        let open_campaign = &mut ctx.accounts.open_campaign;
+        let participation = &mut ctx.accounts.participation;
+
+        if open_campaign.campaign_status != OpenCampaignStatus::Published {
+            return err!(CustomErrorCode::InvalidOpenCampaignStatus);
+        }
+
+        if open_campaign.payout_mode != OpenCampaignPayoutMode::Allocation {
+            return err!(CustomErrorCode::InvalidPayoutMode);
+        }
+
+        if open_campaign.capped_payout(amount) < amount {
+            return err!(CustomErrorCode::PayoutCapExceeded);
+        }
+
+        // Replace any previous allocation and keep the total within the pool
+        let allocated_amount = open_campaign
+            .allocated_amount
+            .checked_sub(participation.allocation)
+            .unwrap()
+            .checked_add(amount)
+            .unwrap();
+        if allocated_amount > open_campaign.pool_amount {
+            return err!(CustomErrorCode::AllocationExceedsPool);
+        }
+
+        open_campaign.allocated_amount = allocated_amount;
+        participation.allocation = amount;
+
+        msg!(
+            "Allocated {} to KOL {:?} in open campaign ID: {:?}",
+            amount,
+            participation.kol,
+            open_campaign.id
+        );
+
+        Ok(())
+    }
+
+    pub fn claim_open_campaign_allocation(ctx: Context<ClaimOpenCampaignAllocation>) -> Result<()> {
+        let decimals = ctx.accounts.token_mint.decimals;
+
+        if ctx.accounts.open_campaign.campaign_status != OpenCampaignStatus::Fulfilled {
+            return err!(CustomErrorCode::InvalidOpenCampaignStatus);
+        }
+
+        if Clock::get()?.unix_timestamp > ctx.accounts.open_campaign.claims_end_at {
+            return err!(CustomErrorCode::ClaimWindowClosed);
+        }
+
+        let open_campaign = &ctx.accounts.open_campaign;
+        let participation = &ctx.accounts.participation;
+        let (allocation, capped_excess) = match open_campaign.payout_mode {
+            OpenCampaignPayoutMode::Allocation => (participation.allocation, 0),
+            OpenCampaignPayoutMode::ProRata => {
+                let share = open_campaign.pro_rata_share(participation.engagement_score);
+                let capped = open_campaign.capped_payout(share);
+                (capped, share - capped)
+            }
+        };
+        if allocation == 0 || participation.claimed {
+            return err!(CustomErrorCode::NothingToClaim);
+        }
+
+        // Platform fee is taken from each allocation as it is claimed, at the
+        // creator's rate, and the claim counts towards their volume
+        let fee_bps = ctx
+            .accounts
+            .marketplace_state
+            .platform_fee_bps(&ctx.accounts.creator_profile, &open_campaign.token_mint);
+        let (kol_amount, _) = split_payout_with_fee(allocation, fee_bps);
+        let owner_amount = allocation - kol_amount;
+        ctx.accounts.creator_profile.record_volume(
+            &open_campaign.creator_address,
+            &open_campaign.token_mint,
+            allocation,
+        );
+
+        let bump = ctx.bumps.open_campaign;
+        transfer_from_open_campaign(
+            &ctx.accounts.open_campaign,
+            bump,
+            &ctx.accounts.campaign_token_account,
+            &ctx.accounts.kol_token_account,
+            &ctx.accounts.token_mint,
+            decimals,
+            &ctx.accounts.token_program,
+            kol_amount,
+        )?;
+        transfer_from_open_campaign(
+            &ctx.accounts.open_campaign,
+            bump,
+            &ctx.accounts.campaign_token_account,
+            &ctx.accounts.owner_token_account,
+            &ctx.accounts.token_mint,
+            decimals,
+            &ctx.accounts.token_program,
+            owner_amount,
+        )?;
+
+        let open_campaign = &mut ctx.accounts.open_campaign;
+        ctx.accounts.participation.claimed = true;
+        open_campaign.claimed_amount = open_campaign
+            .claimed_amount
+            .checked_add(allocation)
+            .unwrap();
+        open_campaign.capped_excess = open_campaign
+            .capped_excess
+            .checked_add(capped_excess)
+            .unwrap();
+        open_campaign.claimed_participants =
+            open_campaign.claimed_participants.checked_add(1).unwrap();
+
+        msg!(
+            "KOL {:?} claimed {} from open campaign ID: {:?}, {} to owner",
+            ctx.accounts.kol.key(),
+            kol_amount,
+            ctx.accounts.open_campaign.id,
+            owner_amount
+        );
+
+        Ok(())
+    }
+
+    pub fn record_engagement_score(
+        ctx: Context<RecordEngagementScore>,
+        engagement_score: u64,
+    ) -> Result<()> {
+        let open_campaign = &mut ctx.accounts.open_campaign;
+        let participation = &mut ctx.accounts.participation;
+
+        if open_campaign.campaign_status != OpenCampaignStatus::Published {
+            return err!(CustomErrorCode::InvalidOpenCampaignStatus);
+        }
+
+        if open_campaign.payout_mode != OpenCampaignPayoutMode::ProRata {
+            return err!(CustomErrorCode::InvalidPayoutMode);
+        }
+
+        // Replace any previous score for this participant
+        open_campaign.total_engagement_score = open_campaign
+            .total_engagement_score
+            .checked_sub(participation.engagement_score)
+            .unwrap()
+            .checked_add(engagement_score)
+            .unwrap();
+        if participation.engagement_score == 0 && engagement_score > 0 {
+            open_campaign.scored_participants =
+                open_campaign.scored_participants.checked_add(1).unwrap();
+        } else if participation.engagement_score > 0 && engagement_score == 0 {
+            open_campaign.scored_participants =
+                open_campaign.scored_participants.checked_sub(1).unwrap();
+        }
+        participation.engagement_score = engagement_score;
+
+        msg!(
+            "Recorded engagement score {} for KOL {:?} in open campaign ID: {:?}",
+            engagement_score,
+            participation.kol,
+            open_campaign.id
+        );
+
+        Ok(())
+    }
+
+    pub fn sweep_open_campaign_dust(ctx: Context<SweepOpenCampaignDust>) -> Result<()> {
+        let decimals = ctx.accounts.token_mint.decimals;
+
+        let open_campaign = &ctx.accounts.open_campaign;
+
+        if open_campaign.campaign_status != OpenCampaignStatus::Fulfilled {
+            return err!(CustomErrorCode::InvalidOpenCampaignStatus);
+        }
+
+        if open_campaign.payout_mode != OpenCampaignPayoutMode::ProRata {
+            return err!(CustomErrorCode::InvalidPayoutMode);
+        }
+
+        // Once the claim window closes, shares nobody claimed go back to the
+        // creator along with everything else left in the vault
+        let claim_window_closed = Clock::get()?.unix_timestamp > open_campaign.claims_end_at;
+        if open_campaign.claimed_participants < open_campaign.scored_participants
+            && !claim_window_closed
+        {
+            return err!(CustomErrorCode::ClaimsOutstanding);
+        }
+
+        // Shares withheld by the per-KOL cap go back to the creator, and
+        // whatever rounding left behind goes to the treasury
+        let creator_amount = if claim_window_closed {
+            ctx.accounts.campaign_token_account.amount
+        } else {
+            open_campaign
+                .capped_excess
+                .min(ctx.accounts.campaign_token_account.amount)
+        };
+        let dust = ctx.accounts.campaign_token_account.amount - creator_amount;
+        transfer_from_open_campaign(
+            open_campaign,
+            ctx.bumps.open_campaign,
+            &ctx.accounts.campaign_token_account,
+            &ctx.accounts.creator_token_account,
+            &ctx.accounts.token_mint,
+            decimals,
+            &ctx.accounts.token_program,
+            creator_amount,
+        )?;
+        transfer_from_open_campaign(
+            open_campaign,
+            ctx.bumps.open_campaign,
+            &ctx.accounts.campaign_token_account,
+            &ctx.accounts.owner_token_account,
+            &ctx.accounts.token_mint,
+            decimals,
+            &ctx.accounts.token_program,
+            dust,
+        )?;
+
+        msg!(
+            "Swept open campaign ID: {:?}: {} to creator, {} dust to treasury",
+            open_campaign.id,
+            creator_amount,
+            dust
+        );
+
+        Ok(())
+    }
+
+    pub fn reclaim_unclaimed_open_campaign_rewards(
+        ctx: Context<ReclaimUnclaimedOpenCampaignRewards>,
+    ) -> Result<()> {
+        let decimals = ctx.accounts.token_mint.decimals;
+
+        let open_campaign = &ctx.accounts.open_campaign;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        if open_campaign.campaign_status != OpenCampaignStatus::Fulfilled {
+            return err!(CustomErrorCode::InvalidOpenCampaignStatus);
+        }
+
+        // Pro-rata leftovers are handled by the dust sweep
+        if open_campaign.payout_mode != OpenCampaignPayoutMode::Allocation {
+            return err!(CustomErrorCode::InvalidPayoutMode);
+        }
+
+        if current_time <= open_campaign.claims_end_at {
+            return err!(CustomErrorCode::ClaimWindowActive);
+        }
+
+        // Unclaimed allocations and Merkle leaves all go back to the creator
+        let refund_amount = ctx.accounts.campaign_token_account.amount;
+        transfer_from_open_campaign(
+            open_campaign,
+            ctx.bumps.open_campaign,
+            &ctx.accounts.campaign_token_account,
+            &ctx.accounts.creator_token_account,
+            &ctx.accounts.token_mint,
+            decimals,
+            &ctx.accounts.token_program,
+            refund_amount,
+        )?;
+
+        emit!(OpenCampaignRewardsReclaimed {
+            campaign_id: open_campaign.id,
+            creator: open_campaign.creator_address,
+            refund_amount,
+            reclaimed_at: current_time,
+        });
+
+        msg!(
+            "Reclaimed {} unclaimed rewards from open campaign ID: {:?}",
+            refund_amount,
+            open_campaign.id
+        );
+
+        Ok(())
+    }
+
+    pub fn initialize_claim_bitmap(
+        ctx: Context<InitializeClaimBitmap>,
+        leaf_count: u32,
+    ) -> Result<()> {
+        // The bitmap is sized for the tree the operator actually posted
+        if ctx.accounts.open_campaign.merkle_root == [0u8; 32] {
+            return err!(CustomErrorCode::InvalidOpenCampaignStatus);
+        }
+
+        if leaf_count != ctx.accounts.open_campaign.merkle_leaf_count {
+            return err!(CustomErrorCode::InvalidParameters);
+        }
+
+        let claim_bitmap = &mut ctx.accounts.claim_bitmap;
+        claim_bitmap.open_campaign = ctx.accounts.open_campaign.key();
+        claim_bitmap.leaf_count = leaf_count;
+        claim_bitmap.claimed = vec![0; (leaf_count as usize).div_ceil(8)];
+
+        msg!(
+            "Claim bitmap initialized for open campaign ID: {:?} with {} leaves",
+            ctx.accounts.open_campaign.id,
+            leaf_count
+        );
+
+        Ok(())
+    }
+
+    pub fn claim_open_reward(
+        ctx: Context<ClaimOpenReward>,
+        index: u32,
+        amount: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        let decimals = ctx.accounts.token_mint.decimals;
+
+        let open_campaign = &ctx.accounts.open_campaign;
+
+        if open_campaign.campaign_status != OpenCampaignStatus::Fulfilled
+            || open_campaign.merkle_root == [0u8; 32]
+        {
+            return err!(CustomErrorCode::InvalidOpenCampaignStatus);
+        }
+
+        if Clock::get()?.unix_timestamp > open_campaign.claims_end_at {
+            return err!(CustomErrorCode::ClaimWindowClosed);
+        }
+
+        if index >= ctx.accounts.claim_bitmap.leaf_count {
+            return err!(CustomErrorCode::InvalidParameters);
+        }
+
+        if ctx.accounts.claim_bitmap.is_claimed(index) {
+            return err!(CustomErrorCode::AlreadyClaimed);
+        }
+
+        let leaf = merkle::leaf_hash(index, &ctx.accounts.kol.key(), amount);
+        if !merkle::verify_proof(&proof, &open_campaign.merkle_root, leaf) {
+            return err!(CustomErrorCode::InvalidMerkleProof);
+        }
+
+        // Never pay out more than the total the operator committed to with the root
+        let merkle_claimed_amount = open_campaign
+            .merkle_claimed_amount
+            .checked_add(amount)
+            .unwrap();
+        if merkle_claimed_amount > open_campaign.merkle_total_amount {
+            return err!(CustomErrorCode::ClaimExceedsPool);
+        }
+
+        let fee_bps = ctx
+            .accounts
+            .marketplace_state
+            .platform_fee_bps(&ctx.accounts.creator_profile, &open_campaign.token_mint);
+        let (kol_amount, _) = split_payout_with_fee(amount, fee_bps);
+        let owner_amount = amount - kol_amount;
+        ctx.accounts.creator_profile.record_volume(
+            &open_campaign.creator_address,
+            &open_campaign.token_mint,
+            amount,
+        );
+
+        let bump = ctx.bumps.open_campaign;
+        transfer_from_open_campaign(
+            &ctx.accounts.open_campaign,
+            bump,
+            &ctx.accounts.campaign_token_account,
+            &ctx.accounts.kol_token_account,
+            &ctx.accounts.token_mint,
+            decimals,
+            &ctx.accounts.token_program,
+            kol_amount,
+        )?;
+        transfer_from_open_campaign(
+            &ctx.accounts.open_campaign,
+            bump,
+            &ctx.accounts.campaign_token_account,
+            &ctx.accounts.owner_token_account,
+            &ctx.accounts.token_mint,
+            decimals,
+            &ctx.accounts.token_program,
+            owner_amount,
+        )?;
+
+        ctx.accounts.claim_bitmap.set_claimed(index);
+        ctx.accounts.open_campaign.merkle_claimed_amount = merkle_claimed_amount;
+
+        msg!(
+            "KOL {:?} claimed leaf {} ({}) from open campaign ID: {:?}, {} to owner",
+            ctx.accounts.kol.key(),
+            index,
+            kol_amount,
+            ctx.accounts.open_campaign.id,
+            owner_amount
+        );
+
+        Ok(())
+    }
+
+    pub fn complete_open_campaign(
+        ctx: Context<CompleteOpenCampaign>,
+        is_fulfilled: bool,
+        merkle_distribution: Option<MerkleDistribution>,
+    ) -> Result<()> {
+        let decimals = ctx.accounts.token_mint.decimals;
+
+        // Check authorization first
+        if ctx.accounts.marketplace_state.owner != ctx.accounts.owner.key() {
+            return err!(CustomErrorCode::Unauthorized);
+        }
+
+        // Store the status check result before mutable borrow
+        let is_published =
+            ctx.accounts.open_campaign.campaign_status == OpenCampaignStatus::Published;
+        if !is_published {
+            return err!(CustomErrorCode::InvalidOpenCampaignStatus);
+        }
+
+        if merkle_distribution.is_some() && !is_fulfilled {
+            return err!(CustomErrorCode::InvalidParameters);
+        }
+
+        // Leaf claims can only draw on the part of the pool not reserved for allocations
+        let unallocated_amount = ctx
+            .accounts
+            .open_campaign
+            .pool_amount
+            .checked_sub(ctx.accounts.open_campaign.allocated_amount)
+            .unwrap();
+        if let Some(distribution) = merkle_distribution {
+            // Leaf amounts can't be checked against the per-KOL cap on-chain
+            if ctx.accounts.open_campaign.max_payout_per_kol > 0 {
+                return err!(CustomErrorCode::InvalidPayoutMode);
+            }
+            if distribution.root == [0u8; 32]
+                || distribution.leaf_count == 0
+                || distribution.leaf_count > MAX_MERKLE_LEAVES
+            {
+                return err!(CustomErrorCode::InvalidParameters);
+            }
+            if distribution.total_amount > unallocated_amount {
+                return err!(CustomErrorCode::ClaimExceedsPool);
+            }
+        }
+
+        // Pro-rata campaigns keep the whole pool in the vault for score-based claims
+        let is_pro_rata = ctx.accounts.open_campaign.payout_mode == OpenCampaignPayoutMode::ProRata;
+        if is_pro_rata && is_fulfilled {
+            if merkle_distribution.is_some() {
+                return err!(CustomErrorCode::InvalidPayoutMode);
+            }
+            if ctx.accounts.open_campaign.total_engagement_score == 0 {
+                return err!(CustomErrorCode::NoEngagementScores);
+            }
+        }
+
+        // `pool_amount` is netted against the transfer fee in effect when it was
+        // recorded, so make sure the vault really holds it
+        if ctx.accounts.campaign_token_account.amount < ctx.accounts.open_campaign.pool_amount {
+            return err!(CustomErrorCode::InsufficientFunds);
+        }
+
+        // Get amounts before mutable borrow. Allocations posted for participants
+        // stay in the vault for them to claim once the campaign is fulfilled, and
+        // a Merkle root keeps its committed total there for leaf claims.
+        // The rest of a fulfilled pool goes back to the creator, and a
+        // discarded pool does too, minus the listing fee.
+        let pool_amount = ctx.accounts.open_campaign.pool_amount;
+        let (owner_amount, creator_amount) = match (is_fulfilled, merkle_distribution) {
+            (true, _) if is_pro_rata => (0, 0),
+            (true, Some(distribution)) => (0, unallocated_amount - distribution.total_amount),
+            (true, None) => (0, unallocated_amount),
+            (false, _) => {
+                let listing_fee = pool_amount
+                    .checked_mul(ctx.accounts.marketplace_state.listing_fee_bps as u64)
+                    .unwrap()
+                    .checked_div(DIVIDER)
+                    .unwrap();
+                (listing_fee, pool_amount - listing_fee)
+            }
+        };
+
+        if let Some(distribution) = merkle_distribution {
+            ctx.accounts.open_campaign.merkle_root = distribution.root;
+            ctx.accounts.open_campaign.merkle_leaf_count = distribution.leaf_count;
+            ctx.accounts.open_campaign.merkle_total_amount = distribution.total_amount;
+        }
+
+        // Update status
+        ctx.accounts.open_campaign.campaign_status = if is_fulfilled {
+            OpenCampaignStatus::Fulfilled
+        } else {
+            OpenCampaignStatus::Discarded
+        };
+        ctx.accounts.open_campaign.claims_end_at = Clock::get()?
+            .unix_timestamp
+            .checked_add(ctx.accounts.marketplace_state.open_campaign_claim_window)
+            .unwrap();
+
+        let bump = ctx.bumps.open_campaign;
+        transfer_from_open_campaign(
+            &ctx.accounts.open_campaign,
+            bump,
+            &ctx.accounts.campaign_token_account,
+            &ctx.accounts.owner_token_account,
+            &ctx.accounts.token_mint,
+            decimals,
+            &ctx.accounts.token_program,
+            owner_amount,
+        )?;
+        transfer_from_open_campaign(
+            &ctx.accounts.open_campaign,
+            bump,
+            &ctx.accounts.campaign_token_account,
+            &ctx.accounts.creator_token_account,
+            &ctx.accounts.token_mint,
+            decimals,
+            &ctx.accounts.token_program,
+            creator_amount,
+        )?;
+
+        msg!(
+            "Open campaign completed with ID: {:?}, status: {:?}",
+            ctx.accounts.open_campaign.id,
+            ctx.accounts.open_campaign.campaign_status
+        );
+
+        Ok(())
+    }
+
+    pub fn close_native_campaign(ctx: Context<CloseNativeCampaign>) -> Result<()> {
+        let campaign = &ctx.accounts.campaign;
+
+        if !matches!(
+            campaign.campaign_status,
+            CampaignStatus::Fulfilled
+                | CampaignStatus::Unfulfilled
+                | CampaignStatus::Discarded
+                | CampaignStatus::Resolved
+        ) {
+            return err!(CustomErrorCode::InvalidCampaignStatus);
+        }
+
+        close_dispute(
+            campaign,
+            &ctx.accounts.dispute,
+            &ctx.accounts.dispute_opener,
+        )?;
+
+        // Any leftover escrow goes back to the creator along with the rent
+        emit!(CampaignClosed {
+            campaign_id: campaign.id,
+            creator: campaign.creator_address,
+            selected_kol: campaign.selected_kol,
+            token_mint: campaign.token_mint,
+            amount_offered: campaign.amount_offered,
+            campaign_status: campaign.campaign_status,
+            closed_at: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Native campaign closed with ID: {:?}", campaign.id);
+
+        Ok(())
+    }
+
+    pub fn close_campaign(ctx: Context<CloseCampaign>) -> Result<()> {
+        let campaign = &ctx.accounts.campaign;
+
+        if !matches!(
+            campaign.campaign_status,
+            CampaignStatus::Fulfilled
+                | CampaignStatus::Unfulfilled
+                | CampaignStatus::Discarded
+                | CampaignStatus::Resolved
+        ) {
+            return err!(CustomErrorCode::InvalidCampaignStatus);
+        }
+
+        if ctx.accounts.campaign_token_account.amount > 0 {
+            return err!(CustomErrorCode::EscrowNotEmpty);
+        }
+
+        if campaign.bundle_legs.iter().any(|leg| !leg.settled) {
+            return err!(CustomErrorCode::BundleLegsOutstanding);
+        }
+
+        if campaign.bond_amount > 0 && !campaign.bond_settled {
+            return err!(CustomErrorCode::BondOutstanding);
+        }
+
+        close_dispute(
+            campaign,
+            &ctx.accounts.dispute,
+            &ctx.accounts.dispute_opener,
+        )?;
+        close_unstarted_vesting(
+            campaign,
+            &ctx.accounts.creator,
+            &ctx.accounts.vesting,
+            &ctx.accounts.vesting_token_account,
+            &ctx.accounts.token_mint,
+            &ctx.accounts.token_program,
+        )?;
+
+        let seeds = &[
+            b"campaign",
+            campaign.creator_address.as_ref(),
+            &campaign.counter.to_le_bytes(),
+            &[ctx.bumps.campaign],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        harvest_withheld_fees(
+            &ctx.accounts.campaign_token_account,
+            &ctx.accounts.token_mint,
+            &ctx.accounts.token_program,
+        )?;
+        token_interface::close_account(CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: ctx.accounts.campaign_token_account.to_account_info(),
+                destination: ctx.accounts.creator.to_account_info(),
+                authority: campaign.to_account_info(),
+            },
+            signer_seeds,
+        ))?;
+
+        emit!(CampaignClosed {
+            campaign_id: campaign.id,
+            creator: campaign.creator_address,
+            selected_kol: campaign.selected_kol,
+            token_mint: campaign.token_mint,
+            amount_offered: campaign.amount_offered,
+            campaign_status: campaign.campaign_status,
+            closed_at: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Campaign closed with ID: {:?}", campaign.id);
+
+        Ok(())
+    }
+
+    pub fn cancel_open_campaign(ctx: Context<CancelOpenCampaign>) -> Result<()> {
+        let decimals = ctx.accounts.token_mint.decimals;
+
+        let open_campaign = &ctx.accounts.open_campaign;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        if open_campaign.campaign_status != OpenCampaignStatus::Published {
+            return err!(CustomErrorCode::InvalidOpenCampaignStatus);
+        }
+
+        // Once KOLs have joined, the creator can only back out during the cooling-off period
+        let cooling_off_ends = open_campaign
+            .created_at
+            .checked_add(ctx.accounts.marketplace_state.open_campaign_cooling_off)
+            .unwrap();
+        if open_campaign.participant_count > 0 && current_time >= cooling_off_ends {
+            return err!(CustomErrorCode::CancellationWindowClosed);
+        }
+
+        // The whole vault goes back, including any top-ups sent straight to it
+        let refund_amount = ctx.accounts.campaign_token_account.amount;
+        transfer_from_open_campaign(
+            open_campaign,
+            ctx.bumps.open_campaign,
+            &ctx.accounts.campaign_token_account,
+            &ctx.accounts.creator_token_account,
+            &ctx.accounts.token_mint,
+            decimals,
+            &ctx.accounts.token_program,
+            refund_amount,
+        )?;
+
+        ctx.accounts.open_campaign.campaign_status = OpenCampaignStatus::Discarded;
+
+        emit!(OpenCampaignCancelled {
+            campaign_id: ctx.accounts.open_campaign.id,
+            creator: ctx.accounts.creator.key(),
+            token_mint: ctx.accounts.open_campaign.token_mint,
+            refund_amount,
+            participant_count: ctx.accounts.open_campaign.participant_count,
+            cancelled_at: current_time,
+        });
+
+        msg!(
+            "Open campaign ID: {:?} cancelled by creator, {} refunded",
+            ctx.accounts.open_campaign.id,
+            refund_amount
+        );
+
+        Ok(())
+    }
+
+    pub fn expire_open_campaign(ctx: Context<ExpireOpenCampaign>) -> Result<()> {
+        let decimals = ctx.accounts.token_mint.decimals;
+
+        let open_campaign = &ctx.accounts.open_campaign;
+        let current_time = Clock::get()?.unix_timestamp;
+
+        if open_campaign.campaign_status != OpenCampaignStatus::Published {
+            return err!(CustomErrorCode::InvalidOpenCampaignStatus);
+        }
+
+        let grace_period_ends = open_campaign
+            .promotion_ends_in
+            .checked_add(ctx.accounts.marketplace_state.open_campaign_grace_period)
+            .unwrap();
+        if current_time < grace_period_ends {
+            return err!(CustomErrorCode::GracePeriodActive);
+        }
+
+        // Payouts the operator already posted are honoured and everything else
+        // goes back to the creator, with no listing fee since nobody settled
+        let (campaign_status, refund_amount) = match open_campaign.payout_mode {
+            OpenCampaignPayoutMode::Allocation if open_campaign.allocated_amount > 0 => (
+                OpenCampaignStatus::Fulfilled,
+                open_campaign
+                    .pool_amount
+                    .checked_sub(open_campaign.allocated_amount)
+                    .unwrap(),
+            ),
+            OpenCampaignPayoutMode::ProRata if open_campaign.total_engagement_score > 0 => {
+                (OpenCampaignStatus::Fulfilled, 0)
+            }
+            _ => (
+                OpenCampaignStatus::Discarded,
+                ctx.accounts.campaign_token_account.amount,
+            ),
+        };
+
+        transfer_from_open_campaign(
+            open_campaign,
+            ctx.bumps.open_campaign,
+            &ctx.accounts.campaign_token_account,
+            &ctx.accounts.creator_token_account,
+            &ctx.accounts.token_mint,
+            decimals,
+            &ctx.accounts.token_program,
+            refund_amount,
+        )?;
+
+        ctx.accounts.open_campaign.campaign_status = campaign_status;
+        ctx.accounts.open_campaign.claims_end_at = current_time
+            .checked_add(ctx.accounts.marketplace_state.open_campaign_claim_window)
+            .unwrap();
+
+        emit!(OpenCampaignExpired {
+            campaign_id: ctx.accounts.open_campaign.id,
+            creator: ctx.accounts.open_campaign.creator_address,
+            campaign_status,
+            refund_amount,
+            expired_by: ctx.accounts.caller.key(),
+            expired_at: current_time,
+        });
+
+        msg!(
+            "Open campaign ID: {:?} expired as {:?}, {} refunded to creator",
+            ctx.accounts.open_campaign.id,
+            campaign_status,
+            refund_amount
+        );
+
+        Ok(())
+    }
+
+    pub fn close_open_campaign(ctx: Context<CloseOpenCampaign>) -> Result<()> {
+        let open_campaign = &ctx.accounts.open_campaign;
+
+        if open_campaign.campaign_status == OpenCampaignStatus::Published {
+            return err!(CustomErrorCode::InvalidOpenCampaignStatus);
+        }
+
+        if ctx.accounts.campaign_token_account.amount > 0 {
+            return err!(CustomErrorCode::EscrowNotEmpty);
+        }
+
+        let seeds = &[
+            b"open_campaign",
+            open_campaign.creator_address.as_ref(),
+            &open_campaign.counter.to_le_bytes(),
+            &[ctx.bumps.open_campaign],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        harvest_withheld_fees(
+            &ctx.accounts.campaign_token_account,
+            &ctx.accounts.token_mint,
+            &ctx.accounts.token_program,
+        )?;
+        token_interface::close_account(CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: ctx.accounts.campaign_token_account.to_account_info(),
+                destination: ctx.accounts.creator.to_account_info(),
+                authority: open_campaign.to_account_info(),
+            },
+            signer_seeds,
+        ))?;
+
+        emit!(OpenCampaignClosed {
+            campaign_id: open_campaign.id,
+            creator: open_campaign.creator_address,
+            token_mint: open_campaign.token_mint,
+            pool_amount: open_campaign.pool_amount,
+            campaign_status: open_campaign.campaign_status,
+            closed_at: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Open campaign closed with ID: {:?}", open_campaign.id);
+
+        Ok(())
+    }
+
+    pub fn create_subscription_campaign(
+        ctx: Context<CreateSubscriptionCampaign>,
+        selected_kol: Pubkey,
+        amount_per_period: u64,
+        total_periods: u32,
+        period_length: i64,
+    ) -> Result<()> {
+        if amount_per_period == 0 {
+            return err!(CustomErrorCode::InvalidAmount);
+        }
+
+        if selected_kol == Pubkey::default() {
+            return err!(CustomErrorCode::InvalidKolAddress);
+        }
+
+        if total_periods == 0 || total_periods > MAX_SUBSCRIPTION_PERIODS || period_length <= 0 {
+            return err!(CustomErrorCode::InvalidTimeParameters);
+        }
+
+        // A period's review has to be able to lapse before the next period ends
+        if ctx.accounts.marketplace_state.review_window > period_length {
+            return err!(CustomErrorCode::InvalidTimeParameters);
+        }
+
+        check_mint_extensions(&ctx.accounts.token_mint)?;
+
+        let current_time = Clock::get()?.unix_timestamp;
+        let creator_key = ctx.accounts.creator.key();
+        let counter = ctx.accounts.marketplace_state.campaign_counter;
+
+        let mut data_to_hash = vec![];
+        data_to_hash.extend_from_slice(&current_time.to_le_bytes());
+        data_to_hash.extend_from_slice(creator_key.as_ref());
+        data_to_hash.extend_from_slice(&counter.to_le_bytes());
+
+        let hashed = hash(&data_to_hash).to_bytes();
+        let id_data = [hashed[0], hashed[1], hashed[2], hashed[3]];
+
+        ctx.accounts.marketplace_state.campaign_counter = ctx
+            .accounts
+            .marketplace_state
+            .campaign_counter
+            .checked_add(1)
+            .unwrap();
+
+        // The creator funds every period up front; any transfer fee is spread
+        // across the periods so the escrow covers each payout exactly
+        let funding_amount = amount_per_period
+            .checked_mul(total_periods as u64)
+            .ok_or(CustomErrorCode::InvalidAmount)?;
+        let net_amount_per_period = net_of_transfer_fee(&ctx.accounts.token_mint, funding_amount)?
+            .checked_div(total_periods as u64)
+            .unwrap();
+
+        let subscription = &mut ctx.accounts.subscription;
+        subscription.id = id_data;
+        subscription.counter = counter;
+        subscription.created_at = current_time;
+        subscription.creator_address = creator_key;
+        subscription.selected_kol = selected_kol;
+        subscription.token_mint = ctx.accounts.token_mint.key();
+        subscription.amount_per_period = net_amount_per_period;
+        subscription.period_length = period_length;
+        subscription.total_periods = total_periods;
+        subscription.status = SubscriptionStatus::Open;
+
+        msg!(
+            "Subscription campaign created with ID: {:?}, creator: {:?}, {} periods of {} tokens",
+            id_data,
+            creator_key,
+            total_periods,
+            net_amount_per_period
+        );
+
+        Ok(())
+    }
+
+    pub fn accept_subscription_campaign(ctx: Context<AcceptSubscriptionCampaign>) -> Result<()> {
+        let subscription = &mut ctx.accounts.subscription;
+
+        if subscription.status != SubscriptionStatus::Open {
+            return err!(CustomErrorCode::InvalidSubscriptionStatus);
+        }
+
+        // Only accept once every period is actually escrowed
+        let required_amount = subscription
+            .amount_per_period
+            .checked_mul(subscription.total_periods as u64)
+            .unwrap();
+        if ctx.accounts.subscription_token_account.amount < required_amount {
+            return err!(CustomErrorCode::InsufficientFunds);
+        }
+
+        subscription.started_at = Clock::get()?.unix_timestamp;
+        subscription.status = SubscriptionStatus::Active;
+
+        msg!(
+            "Subscription campaign accepted with ID: {:?}, accepted by: {:?}",
+            subscription.id,
+            ctx.accounts.kol.key()
+        );
+
+        Ok(())
+    }
+
+    pub fn submit_subscription_deliverable(
+        ctx: Context<SubmitSubscriptionDeliverable>,
+        content_hash: [u8; 32],
+    ) -> Result<()> {
+        if content_hash == [0u8; 32] {
+            return err!(CustomErrorCode::InvalidParameters);
+        }
+
+        let subscription = &mut ctx.accounts.subscription;
+        if subscription.status != SubscriptionStatus::Active {
+            return err!(CustomErrorCode::InvalidSubscriptionStatus);
+        }
+
+        if subscription.has_pending_deliverable() {
+            return err!(CustomErrorCode::SubmissionPending);
+        }
+
+        let current_time = Clock::get()?.unix_timestamp;
+        let period = subscription.current_period(current_time);
+        if period >= subscription.total_periods as u64 {
+            return err!(CustomErrorCode::CampaignExpired);
+        }
+
+        if period < subscription.next_period as u64 {
+            return err!(CustomErrorCode::PeriodAlreadySettled);
+        }
+
+        subscription.pending_period = period as u32;
+        subscription.deliverable_hash = content_hash;
+        subscription.submitted_at = current_time;
+        subscription.review_ends_in = current_time
+            .checked_add(ctx.accounts.marketplace_state.review_window)
+            .unwrap();
+
+        msg!(
+            "Deliverable submitted for period {} of subscription ID: {:?}",
+            period,
+            subscription.id
+        );
+
+        Ok(())
+    }
+
+    pub fn fulfil_subscription_period(ctx: Context<FulfilSubscriptionPeriod>) -> Result<()> {
+        let decimals = ctx.accounts.token_mint.decimals;
+
+        let subscription = &ctx.accounts.subscription;
+        if subscription.status != SubscriptionStatus::Active {
+            return err!(CustomErrorCode::InvalidSubscriptionStatus);
+        }
+
+        if !subscription.has_pending_deliverable() {
+            return err!(CustomErrorCode::NoPendingSubmission);
+        }
+
+        // The creator approves the period; anyone can release it once the review window lapses
+        let current_time = Clock::get()?.unix_timestamp;
+        if ctx.accounts.caller.key() != subscription.creator_address
+            && current_time < subscription.review_ends_in
+        {
+            return err!(CustomErrorCode::ReviewWindowActive);
+        }
+
+        // Same terms as a campaign payout: the creator's fee rate, with the
+        // referrer's cut out of the fee and the agency's out of the KOL share
+        let amount = subscription.amount_per_period;
+        let fee_bps = ctx
+            .accounts
+            .marketplace_state
+            .platform_fee_bps(&ctx.accounts.creator_profile, &subscription.token_mint);
+        let (kol_amount, _) = split_payout_with_fee(amount, fee_bps);
+        let owner_amount = amount - kol_amount;
+        let referrer_share = revenue_share(
+            ctx.accounts.referrer_token_account.as_ref(),
+            subscription.referrer,
+            subscription.referrer_share_bps,
+            owner_amount,
+            &subscription.token_mint,
+        )?;
+        let agency_share = revenue_share(
+            ctx.accounts.agency_token_account.as_ref(),
+            subscription.agency,
+            subscription.agency_share_bps,
+            kol_amount,
+            &subscription.token_mint,
+        )?;
+        let owner_amount = owner_amount - referrer_share.map_or(0, |(_, amount)| amount);
+        let kol_amount = kol_amount - agency_share.map_or(0, |(_, amount)| amount);
+
+        let bump = ctx.bumps.subscription;
+        for (to, amount) in [
+            (&ctx.accounts.kol_token_account, kol_amount),
+            (&ctx.accounts.owner_token_account, owner_amount),
+        ]
+        .into_iter()
+        .chain(referrer_share)
+        .chain(agency_share)
+        {
+            transfer_from_subscription(
+                subscription,
+                bump,
+                &ctx.accounts.subscription_token_account,
+                to,
+                &ctx.accounts.token_mint,
+                decimals,
+                &ctx.accounts.token_program,
+                amount,
+            )?;
+        }
+
+        ctx.accounts.creator_profile.record_volume(
+            &subscription.creator_address,
+            &subscription.token_mint,
+            amount,
+        );
+
+        let period = subscription.pending_period;
+        let subscription = &mut ctx.accounts.subscription;
+        subscription.periods_paid = subscription.periods_paid.checked_add(1).unwrap();
+        subscription.next_period = period.checked_add(1).unwrap();
+        subscription.deliverable_hash = [0u8; 32];
+
+        // After the last period whatever is left (skipped periods, rounding) goes back
+        let mut refund_amount = 0;
+        if subscription.next_period >= subscription.total_periods {
+            ctx.accounts.subscription_token_account.reload()?;
+            refund_amount = ctx.accounts.subscription_token_account.amount;
+            transfer_from_subscription(
+                &ctx.accounts.subscription,
+                bump,
+                &ctx.accounts.subscription_token_account,
+                &ctx.accounts.creator_token_account,
+                &ctx.accounts.token_mint,
+                decimals,
+                &ctx.accounts.token_program,
+                refund_amount,
+            )?;
+            ctx.accounts.subscription.status = SubscriptionStatus::Completed;
+        }
+
+        emit!(SubscriptionPeriodPaid {
+            subscription_id: ctx.accounts.subscription.id,
+            period,
+            kol_amount,
+            owner_amount,
+            refund_amount,
+        });
+
+        msg!(
+            "Paid period {} of subscription ID: {:?}. Transferred {} to KOL and {} to owner",
+            period,
+            ctx.accounts.subscription.id,
+            kol_amount,
+            owner_amount
+        );
+
+        Ok(())
+    }
+
+    pub fn terminate_subscription_campaign(
+        ctx: Context<TerminateSubscriptionCampaign>,
+    ) -> Result<()> {
+        let decimals = ctx.accounts.token_mint.decimals;
+
+        let party = ctx.accounts.party.key();
+        let subscription = &ctx.accounts.subscription;
+        if party != subscription.creator_address && party != subscription.selected_kol {
+            return err!(CustomErrorCode::Unauthorized);
+        }
+
+        if subscription.status != SubscriptionStatus::Open
+            && subscription.status != SubscriptionStatus::Active
+        {
+            return err!(CustomErrorCode::InvalidSubscriptionStatus);
+        }
+
+        // A submitted period has to be paid out (or released) before anyone walks away
+        if subscription.has_pending_deliverable() {
+            return err!(CustomErrorCode::SubmissionPending);
+        }
+
+        let refund_amount = ctx.accounts.subscription_token_account.amount;
+        transfer_from_subscription(
+            subscription,
+            ctx.bumps.subscription,
+            &ctx.accounts.subscription_token_account,
+            &ctx.accounts.creator_token_account,
+            &ctx.accounts.token_mint,
+            decimals,
+            &ctx.accounts.token_program,
+            refund_amount,
+        )?;
+
+        let unused_periods = subscription.total_periods - subscription.next_period;
+        ctx.accounts.subscription.status = SubscriptionStatus::Terminated;
+
+        emit!(SubscriptionTerminated {
+            subscription_id: ctx.accounts.subscription.id,
+            terminated_by: party,
+            periods_paid: ctx.accounts.subscription.periods_paid,
+            unused_periods,
+            refund_amount,
+        });
+
+        msg!(
+            "Subscription ID: {:?} terminated by {:?}. Refunded {} to creator",
+            ctx.accounts.subscription.id,
+            party,
+            refund_amount
+        );
+
+        Ok(())
+    }
+
+    pub fn expire_subscription_campaign(ctx: Context<ExpireSubscriptionCampaign>) -> Result<()> {
+        let decimals = ctx.accounts.token_mint.decimals;
+
+        let subscription = &ctx.accounts.subscription;
+        if subscription.status != SubscriptionStatus::Active {
+            return err!(CustomErrorCode::InvalidSubscriptionStatus);
+        }
+
+        // Anyone can wind the subscription down once its last period has ended;
+        // a submitted period still gets paid (or released) first
+        let current_time = Clock::get()?.unix_timestamp;
+        if subscription.current_period(current_time) < subscription.total_periods as u64 {
+            return err!(CustomErrorCode::InvalidTimeParameters);
+        }
+
+        if subscription.has_pending_deliverable() {
+            return err!(CustomErrorCode::SubmissionPending);
+        }
+
+        let refund_amount = ctx.accounts.subscription_token_account.amount;
+        transfer_from_subscription(
+            subscription,
+            ctx.bumps.subscription,
+            &ctx.accounts.subscription_token_account,
+            &ctx.accounts.creator_token_account,
+            &ctx.accounts.token_mint,
+            decimals,
+            &ctx.accounts.token_program,
+            refund_amount,
+        )?;
+
+        let unused_periods = subscription.total_periods - subscription.next_period;
+        ctx.accounts.subscription.status = SubscriptionStatus::Completed;
+
+        emit!(SubscriptionExpired {
+            subscription_id: ctx.accounts.subscription.id,
+            periods_paid: ctx.accounts.subscription.periods_paid,
+            unused_periods,
+            refund_amount,
+            expired_by: ctx.accounts.caller.key(),
+            expired_at: current_time,
+        });
+
+        msg!(
+            "Subscription ID: {:?} expired. Refunded {} to creator",
+            ctx.accounts.subscription.id,
+            refund_amount
+        );
+
+        Ok(())
+    }
+
+    pub fn set_subscription_referrer(
+        ctx: Context<SetSubscriptionReferrer>,
+        referrer: Pubkey,
+        referrer_share_bps: u16,
+    ) -> Result<()> {
+        if referrer_share_bps > ctx.accounts.marketplace_state.max_referrer_share_bps {
+            return err!(CustomErrorCode::RevenueShareTooHigh);
+        }
+
+        let subscription = &mut ctx.accounts.subscription;
+        if subscription.status != SubscriptionStatus::Open {
+            return err!(CustomErrorCode::InvalidSubscriptionStatus);
+        }
+
+        subscription.referrer = referrer;
+        subscription.referrer_share_bps = referrer_share_bps;
+
+        msg!(
+            "Referrer {:?} set for subscription ID: {:?} with {} bps of the platform fee",
+            referrer,
+            subscription.id,
+            referrer_share_bps
+        );
+
+        Ok(())
+    }
+
+    pub fn set_subscription_agency(
+        ctx: Context<SetSubscriptionAgency>,
+        agency: Pubkey,
+        agency_share_bps: u16,
+    ) -> Result<()> {
+        if agency_share_bps > ctx.accounts.marketplace_state.max_agency_share_bps {
+            return err!(CustomErrorCode::RevenueShareTooHigh);
+        }
+
+        let subscription = &mut ctx.accounts.subscription;
+        if subscription.status != SubscriptionStatus::Open
+            && subscription.status != SubscriptionStatus::Active
+        {
+            return err!(CustomErrorCode::InvalidSubscriptionStatus);
+        }
+
+        // Changing the split under a submitted period would re-price its payout
+        if subscription.has_pending_deliverable() {
+            return err!(CustomErrorCode::SubmissionPending);
+        }
+
+        subscription.agency = agency;
+        subscription.agency_share_bps = agency_share_bps;
+
+        msg!(
+            "Agency {:?} set for subscription ID: {:?} with {} bps of the KOL share",
+            agency,
+            subscription.id,
+            agency_share_bps
+        );
+
+        Ok(())
+    }
+
+    pub fn close_subscription_campaign(ctx: Context<CloseSubscriptionCampaign>) -> Result<()> {
+        let subscription = &ctx.accounts.subscription;
+
+        if subscription.status != SubscriptionStatus::Completed
+            && subscription.status != SubscriptionStatus::Terminated
+        {
+            return err!(CustomErrorCode::InvalidSubscriptionStatus);
+        }
+
+        if ctx.accounts.subscription_token_account.amount > 0 {
+            return err!(CustomErrorCode::EscrowNotEmpty);
+        }
+
+        let seeds = &[
+            b"subscription",
+            subscription.creator_address.as_ref(),
+            &subscription.counter.to_le_bytes(),
+            &[ctx.bumps.subscription],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        harvest_withheld_fees(
+            &ctx.accounts.subscription_token_account,
+            &ctx.accounts.token_mint,
+            &ctx.accounts.token_program,
+        )?;
+        token_interface::close_account(CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: ctx.accounts.subscription_token_account.to_account_info(),
+                destination: ctx.accounts.creator.to_account_info(),
+                authority: subscription.to_account_info(),
+            },
+            signer_seeds,
+        ))?;
+
+        emit!(SubscriptionClosed {
+            subscription_id: subscription.id,
+            creator: subscription.creator_address,
+            selected_kol: subscription.selected_kol,
+            periods_paid: subscription.periods_paid,
+            status: subscription.status,
+            closed_at: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Subscription closed with ID: {:?}", subscription.id);
+
+        Ok(())
+    }
+
+    pub fn add_allowed_token(
+        ctx: Context<UpdateAllowedTokens>,
+        token_mint: Pubkey,
+        token_decimal: u8,
+    ) -> Result<()> {
+        // Check if the token is already allowed
+        if ctx
+            .accounts
+            .marketplace_state
+            .allowed_tokens
+            .contains(&token_mint)
+        {
+            return err!(CustomErrorCode::TokenAlreadyAllowed);
+        }
+
+        // Check if we're not exceeding the maximum allowed tokens (20 as per space allocation)
+        require!(
+            ctx.accounts.marketplace_state.allowed_tokens.len() < 20,
+            CustomErrorCode::TooManyTokens
+        );
+
+        // Add the new token and its decimal
+        ctx.accounts
+            .marketplace_state
+            .allowed_tokens
+            .push(token_mint);
+        ctx.accounts
+            .marketplace_state
+            .token_decimals
+            .push(token_decimal);
+
+        msg!("Added new allowed token: {}", token_mint);
+        Ok(())
+    }
+
+    pub fn update_review_window(
+        ctx: Context<UpdateMarketplaceSettings>,
+        review_window: i64,
+    ) -> Result<()> {
+        if review_window < 0 {
+            return err!(CustomErrorCode::InvalidTimeParameters);
+        }
+
+        ctx.accounts.marketplace_state.review_window = review_window;
+
+        msg!("Updated review window to {} seconds", review_window);
+        Ok(())
+    }
+
+    pub fn update_arbiter(ctx: Context<UpdateMarketplaceSettings>, arbiter: Pubkey) -> Result<()> {
+        if arbiter == Pubkey::default() {
+            return err!(CustomErrorCode::InvalidParameters);
+        }
+
+        ctx.accounts.marketplace_state.arbiter = arbiter;
+
+        msg!("Updated arbiter to {}", arbiter);
+        Ok(())
+    }
+
+    pub fn update_listing_fee(
+        ctx: Context<UpdateMarketplaceSettings>,
+        listing_fee_bps: u16,
+    ) -> Result<()> {
+        if listing_fee_bps as u64 > DIVIDER {
+            return err!(CustomErrorCode::InvalidParameters);
+        }
+
+        ctx.accounts.marketplace_state.listing_fee_bps = listing_fee_bps;
+
+        msg!("Updated listing fee to {} bps", listing_fee_bps);
+        Ok(())
+    }
+
+    pub fn update_max_deadline_extension(
+        ctx: Context<UpdateMarketplaceSettings>,
+        max_deadline_extension: i64,
+    ) -> Result<()> {
+        if max_deadline_extension < 0 {
+            return err!(CustomErrorCode::InvalidTimeParameters);
+        }
+
+        ctx.accounts.marketplace_state.max_deadline_extension = max_deadline_extension;
+
+        msg!(
+            "Updated max deadline extension to {} seconds",
+            max_deadline_extension
+        );
+        Ok(())
+    }
+
+    pub fn update_oracle(ctx: Context<UpdateMarketplaceSettings>, oracle: Pubkey) -> Result<()> {
+        if oracle == Pubkey::default() {
+            return err!(CustomErrorCode::InvalidParameters);
+        }
+
+        ctx.accounts.marketplace_state.oracle = oracle;
+
+        msg!("Updated oracle to {}", oracle);
+        Ok(())
+    }
+
+    pub fn update_open_campaign_cooling_off(
+        ctx: Context<UpdateMarketplaceSettings>,
+        open_campaign_cooling_off: i64,
+    ) -> Result<()> {
+        if open_campaign_cooling_off < 0 {
+            return err!(CustomErrorCode::InvalidTimeParameters);
+        }
+
+        ctx.accounts.marketplace_state.open_campaign_cooling_off = open_campaign_cooling_off;
+
+        msg!(
+            "Updated open campaign cooling-off period to {} seconds",
+            open_campaign_cooling_off
+        );
+        Ok(())
+    }
+
+    pub fn update_open_campaign_grace_period(
+        ctx: Context<UpdateMarketplaceSettings>,
+        open_campaign_grace_period: i64,
+    ) -> Result<()> {
+        if open_campaign_grace_period < 0 {
+            return err!(CustomErrorCode::InvalidTimeParameters);
+        }
+
+        ctx.accounts.marketplace_state.open_campaign_grace_period = open_campaign_grace_period;
+
+        msg!(
+            "Updated open campaign grace period to {} seconds",
+            open_campaign_grace_period
+        );
+        Ok(())
+    }
+
+    pub fn update_open_campaign_claim_window(
+        ctx: Context<UpdateMarketplaceSettings>,
+        open_campaign_claim_window: i64,
+    ) -> Result<()> {
+        if open_campaign_claim_window < 0 {
+            return err!(CustomErrorCode::InvalidTimeParameters);
+        }
+
+        ctx.accounts.marketplace_state.open_campaign_claim_window = open_campaign_claim_window;
+
+        msg!(
+            "Updated open campaign claim window to {} seconds",
+            open_campaign_claim_window
+        );
+        Ok(())
+    }
+
+    pub fn update_revenue_share_limits(
+        ctx: Context<UpdateMarketplaceSettings>,
+        max_referrer_share_bps: u16,
+        max_agency_share_bps: u16,
+    ) -> Result<()> {
+        if max_referrer_share_bps as u64 > DIVIDER || max_agency_share_bps as u64 > DIVIDER {
+            return err!(CustomErrorCode::InvalidParameters);
+        }
+
+        ctx.accounts.marketplace_state.max_referrer_share_bps = max_referrer_share_bps;
+        ctx.accounts.marketplace_state.max_agency_share_bps = max_agency_share_bps;
+
+        msg!(
+            "Updated revenue share limits to {} bps for referrers and {} bps for agencies",
+            max_referrer_share_bps,
+            max_agency_share_bps
+        );
+        Ok(())
+    }
+
+    pub fn set_fee_tiers(
+        ctx: Context<UpdateMarketplaceSettings>,
+        fee_tiers: Vec<FeeTier>,
+    ) -> Result<()> {
+        if fee_tiers.len() > MAX_FEE_TIERS {
+            return err!(CustomErrorCode::TooManyFeeTiers);
+        }
+
+        // Tiers only ever discount the default platform fee
+        if fee_tiers
+            .iter()
+            .any(|tier| tier.fee_bps as u64 > OWNER_SHARE_PERCENTAGE)
+        {
+            return err!(CustomErrorCode::InvalidParameters);
+        }
+
+        msg!("Updated fee tiers: {} tiers", fee_tiers.len());
+        ctx.accounts.marketplace_state.fee_tiers = fee_tiers;
+        Ok(())
+    }
+
+    pub fn set_creator_fee_override(
+        ctx: Context<SetCreatorFeeOverride>,
+        creator: Pubkey,
+        fee_override_bps: Option<u16>,
+    ) -> Result<()> {
+        if fee_override_bps.is_some_and(|fee_bps| fee_bps as u64 > DIVIDER) {
+            return err!(CustomErrorCode::InvalidParameters);
+        }
+
+        let creator_profile = &mut ctx.accounts.creator_profile;
+        creator_profile.creator = creator;
+        creator_profile.fee_override_bps = fee_override_bps;
+
+        msg!(
+            "Set fee override of creator {:?} to {:?} bps",
+            creator,
+            fee_override_bps
+        );
+        Ok(())
+    }
+
+    pub fn update_bond_slash_treasury_bps(
+        ctx: Context<UpdateMarketplaceSettings>,
+        bond_slash_treasury_bps: u16,
+    ) -> Result<()> {
+        if bond_slash_treasury_bps as u64 > DIVIDER {
+            return err!(CustomErrorCode::InvalidParameters);
+        }
+
+        ctx.accounts.marketplace_state.bond_slash_treasury_bps = bond_slash_treasury_bps;
+
+        msg!(
+            "Updated bond slash treasury share to {} bps",
+            bond_slash_treasury_bps
+        );
+        Ok(())
+    }
+
+    pub fn set_price_feed(
+        ctx: Context<SetPriceFeed>,
+        token_mint: Pubkey,
+        price_feed: Pubkey,
+        max_staleness: i64,
+        max_confidence_bps: u16,
+    ) -> Result<()> {
+        if max_staleness < 0 {
+            return err!(CustomErrorCode::InvalidTimeParameters);
+        }
+
+        if max_confidence_bps as u64 > DIVIDER {
+            return err!(CustomErrorCode::InvalidParameters);
+        }
+
+        let price_feed_config = &mut ctx.accounts.price_feed_config;
+        price_feed_config.token_mint = token_mint;
+        price_feed_config.price_feed = price_feed;
+        price_feed_config.max_staleness = max_staleness;
+        price_feed_config.max_confidence_bps = max_confidence_bps;
+
+        msg!("Set price feed of token {} to {}", token_mint, price_feed);
+        Ok(())
+    }
+
+    pub fn remove_allowed_token(
+        ctx: Context<UpdateAllowedTokens>,
+        token_mint: Pubkey,
+    ) -> Result<()> {
+        let marketplace_state = &mut ctx.accounts.marketplace_state;
+
+        // Find the index of the token to remove
+        if let Some(index) = marketplace_state
+            .allowed_tokens
+            .iter()
+            .position(|x| x == &token_mint)
+        {
+            // Remove the token and its corresponding decimal
+            marketplace_state.allowed_tokens.remove(index);
+            marketplace_state.token_decimals.remove(index);
+
+            msg!("Removed token: {}", token_mint);
+            Ok(())
+        } else {
+            err!(CustomErrorCode::TokenNotFound)
+        }
+    }
+}
+
+/// Moves `bond_amount` from the KOL into the campaign's bond vault, the ATA of
+/// the `[b"bond", campaign]` PDA so it never mixes with the escrow.
+fn post_kol_bond(ctx: &Context<AcceptProjectCampaign>, bond_amount: u64) -> Result<()> {
+    let campaign = &ctx.accounts.campaign;
+    if campaign.is_native {
+        return err!(sol_cb::CustomErrorCode::NativeCampaignUnsupported);
+    }
+
+    let (Some(kol_token_account), Some(bond_token_account), Some(token_mint), Some(token_program)) = (
+        &ctx.accounts.kol_token_account,
+        &ctx.accounts.bond_token_account,
+        &ctx.accounts.token_mint,
+        &ctx.accounts.token_program,
+    ) else {
+        return err!(sol_cb::CustomErrorCode::BondAccountsMissing);
+    };
+
+    let (bond_authority, _) =
+        Pubkey::find_program_address(&[b"bond", campaign.key().as_ref()], &crate::ID);
+    if token_mint.key() != campaign.token_mint
+        || bond_token_account.key()
+            != get_associated_token_address_with_program_id(
+                &bond_authority,
+                &campaign.token_mint,
+                &token_program.key(),
+            )
+    {
+        return err!(sol_cb::CustomErrorCode::InvalidParameters);
+    }
+
+    let decimals = token_mint.decimals;
+
+    token_interface::transfer_checked(
+        CpiContext::new(
+            token_program.to_account_info(),
+            TransferChecked {
+                from: kol_token_account.to_account_info(),
+                mint: token_mint.to_account_info(),
+                to: bond_token_account.to_account_info(),
+                authority: ctx.accounts.kol.to_account_info(),
+            },
+        ),
+        bond_amount,
+        decimals,
+    )
+}
+
+/// Closes the dispute of a resolved campaign and refunds its rent to whoever
+/// opened it. Bonds and bundle legs read the ruling, so this only runs once
+/// the campaign itself is closed.
+fn close_dispute<'info>(
+    campaign: &Account<'info, Campaign>,
+    dispute: &Option<Account<'info, Dispute>>,
+    dispute_opener: &Option<UncheckedAccount<'info>>,
+) -> Result<()> {
+    if campaign.campaign_status != CampaignStatus::Resolved {
+        return Ok(());
+    }
+
+    match (dispute, dispute_opener) {
+        (Some(dispute), Some(dispute_opener)) if dispute_opener.key() == dispute.opened_by => {
+            dispute.close(dispute_opener.to_account_info())
+        }
+        _ => err!(sol_cb::CustomErrorCode::InvalidParameters),
+    }
+}
+
+/// Closes the vesting account of a campaign that was discarded or went
+/// unfulfilled, and its vault if one was opened, refunding both to the
+/// creator. Every other closable campaign started its schedule at payout, and
+/// the KOL's final claim closes those.
+fn close_unstarted_vesting<'info>(
+    campaign: &Account<'info, Campaign>,
+    creator: &Signer<'info>,
+    vesting: &Option<Account<'info, Vesting>>,
+    vesting_token_account: &Option<InterfaceAccount<'info, TokenAccount>>,
+    token_mint: &InterfaceAccount<'info, Mint>,
+    token_program: &Interface<'info, TokenInterface>,
+) -> Result<()> {
+    if campaign.vesting_duration == 0
+        || !matches!(
+            campaign.campaign_status,
+            CampaignStatus::Discarded | CampaignStatus::Unfulfilled
+        )
+    {
+        return Ok(());
+    }
+
+    let Some(vesting) = vesting else {
+        return err!(sol_cb::CustomErrorCode::VestingAccountsMissing);
+    };
+
+    if let Some(vesting_token_account) = vesting_token_account {
+        if vesting_token_account.key()
+            != get_associated_token_address_with_program_id(
+                &vesting.key(),
+                &campaign.token_mint,
+                &token_program.key(),
+            )
+        {
+            return err!(sol_cb::CustomErrorCode::VestingAccountsMissing);
+        }
+
+        if vesting_token_account.amount > 0 {
+            return err!(sol_cb::CustomErrorCode::EscrowNotEmpty);
+        }
+
+        let campaign_key = campaign.key();
+        let (_, bump) =
+            Pubkey::find_program_address(&[b"vesting", campaign_key.as_ref()], &crate::ID);
+        let seeds = &[b"vesting", campaign_key.as_ref(), &[bump]];
+        let signer_seeds = &[&seeds[..]];
+
+        harvest_withheld_fees(vesting_token_account, token_mint, token_program)?;
+        token_interface::close_account(CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            CloseAccount {
+                account: vesting_token_account.to_account_info(),
+                destination: creator.to_account_info(),
+                authority: vesting.to_account_info(),
+            },
+            signer_seeds,
+        ))?;
+    }
+
+    vesting.close(creator.to_account_info())
+}
+
+/// Where the KOL share of a payout goes: the vesting vault (the vesting
+/// account's ATA) for campaigns with a vesting schedule, the KOL's own token
+/// account otherwise.
+fn vesting_destination<'a, 'info>(
+    campaign: &Campaign,
+    vesting: &Option<Account<'info, Vesting>>,
+    vesting_token_account: &'a Option<InterfaceAccount<'info, TokenAccount>>,
+    kol_token_account: &'a InterfaceAccount<'info, TokenAccount>,
+) -> Result<&'a InterfaceAccount<'info, TokenAccount>> {
+    if campaign.vesting_duration == 0 {
+        return Ok(kol_token_account);
+    }
+
+    match (vesting, vesting_token_account) {
+        (Some(vesting), Some(vesting_token_account))
+            if vesting_token_account.key()
+                == get_associated_token_address_with_program_id(
+                    &vesting.key(),
+                    &campaign.token_mint,
+                    vesting_token_account.to_account_info().owner,
+                ) =>
+        {
+            Ok(vesting_token_account)
+        }
+        _ => err!(sol_cb::CustomErrorCode::VestingAccountsMissing),
+    }
+}
+
+/// Starts the vesting schedule for `kol_amount` once a vesting campaign pays out.
+fn start_vesting(
+    campaign: &Campaign,
+    vesting: &mut Option<Account<Vesting>>,
+    kol_amount: u64,
+) -> Result<()> {
+    if campaign.vesting_duration == 0 {
+        return Ok(());
+    }
+
+    let Some(vesting) = vesting else {
+        return err!(sol_cb::CustomErrorCode::VestingAccountsMissing);
+    };
+    let current_time = Clock::get()?.unix_timestamp;
+    vesting.kol = campaign.selected_kol;
+    vesting.token_mint = campaign.token_mint;
+    vesting.total_amount = kol_amount;
+    vesting.start_ts = current_time;
+    vesting.cliff_ts = current_time.checked_add(campaign.vesting_cliff).unwrap();
+    vesting.end_ts = current_time.checked_add(campaign.vesting_duration).unwrap();
+
+    Ok(())
+}
+
+/// Token amount worth `usd_value` according to the mint's configured price feed.
+fn quote_usd_value(
+    price_feed_config: &PriceFeedConfig,
+    price_feed: &AccountInfo,
+    usd_value: u64,
+    decimals: u8,
+) -> Result<u64> {
+    let price = price_feed::PriceFeed::load(price_feed)?;
+    price.check(
+        Clock::get()?.unix_timestamp,
+        price_feed_config.max_staleness,
+        price_feed_config.max_confidence_bps,
+    )?;
+
+    match price.token_amount_for_usd(usd_value, decimals) {
+        Some(amount) => Ok(amount),
+        None => err!(sol_cb::CustomErrorCode::InvalidAmount),
+    }
+}
+
+/// Re-prices a USD-priced campaign against the mint's price feed right before it
+/// pays out, so the rate can't be picked ahead of time. A shortfall is only
+/// topped up when the creator signs the payout; any surplus goes back to them.
+#[allow(clippy::too_many_arguments)]
+fn settle_usd_price<'info>(
+    campaign: &mut Account<'info, Campaign>,
+    bump: u8,
+    price_feed_config: &Option<Account<'info, PriceFeedConfig>>,
+    price_feed: &Option<UncheckedAccount<'info>>,
+    campaign_token_account: &mut InterfaceAccount<'info, TokenAccount>,
+    creator_token_account: &Option<InterfaceAccount<'info, TokenAccount>>,
+    creator: Option<&Signer<'info>>,
+    token_mint: &InterfaceAccount<'info, Mint>,
+    token_program: &Interface<'info, TokenInterface>,
+) -> Result<()> {
+    if campaign.usd_value == 0 {
+        return Ok(());
+    }
+
+    let (Some(price_feed_config), Some(price_feed), Some(creator_token_account)) =
+        (price_feed_config, price_feed, creator_token_account)
+    else {
+        return err!(sol_cb::CustomErrorCode::PriceFeedAccountsMissing);
+    };
+    if price_feed.key() != price_feed_config.price_feed {
+        return err!(sol_cb::CustomErrorCode::InvalidPriceFeed);
+    }
+
+    let decimals = token_mint.decimals;
+    let required_amount = quote_usd_value(
+        price_feed_config,
+        &price_feed.to_account_info(),
+        campaign.usd_value,
+        decimals,
+    )?;
+
+    let mut top_up_amount = 0;
+    if let Some(creator) = creator {
+        if required_amount > campaign_token_account.amount {
+            top_up_amount = required_amount - campaign_token_account.amount;
+            token_interface::transfer_checked(
+                CpiContext::new(
+                    token_program.to_account_info(),
+                    TransferChecked {
+                        from: creator_token_account.to_account_info(),
+                        mint: token_mint.to_account_info(),
+                        to: campaign_token_account.to_account_info(),
+                        authority: creator.to_account_info(),
+                    },
+                ),
+                top_up_amount,
+                decimals,
+            )?;
+            campaign_token_account.reload()?;
+        }
+    }
+
+    let campaign_balance = campaign_token_account.amount;
+    let settled_amount = required_amount.min(campaign_balance);
+    let refund_amount = campaign_balance - settled_amount;
+
+    transfer_from_campaign(
+        campaign,
+        bump,
+        campaign_token_account,
+        creator_token_account,
+        token_mint,
+        decimals,
+        token_program,
+        refund_amount,
+    )?;
+    campaign_token_account.reload()?;
+
+    let current_time = Clock::get()?.unix_timestamp;
+    campaign.amount_offered = settled_amount;
+    campaign.usd_settled_at = current_time;
+
+    emit!(UsdPriceSettled {
+        campaign_id: campaign.id,
+        usd_value: campaign.usd_value,
+        amount_offered: settled_amount,
+        top_up_amount,
+        refund_amount,
+        settled_at: current_time,
+    });
+
+    msg!(
+        "USD price settled for campaign ID: {:?}. Amount offered {}, topped up {}, refunded {}",
+        campaign.id,
+        settled_amount,
+        top_up_amount,
+        refund_amount
+    );
+
+    Ok(())
+}
+
+/// Accounts a direct project campaign pays out of, borrowed from the fulfil,
+/// approve and release contexts.
+struct DirectPayout<'a, 'info> {
+    marketplace_state: &'a MarketplaceState,
+    campaign: &'a mut Account<'info, Campaign>,
+    campaign_bump: u8,
+    creator_profile: &'a mut Account<'info, CreatorProfile>,
+    campaign_token_account: &'a mut InterfaceAccount<'info, TokenAccount>,
+    kol_token_account: &'a InterfaceAccount<'info, TokenAccount>,
+    vesting: &'a mut Option<Account<'info, Vesting>>,
+    vesting_token_account: &'a Option<InterfaceAccount<'info, TokenAccount>>,
+    owner_token_account: &'a InterfaceAccount<'info, TokenAccount>,
+    referrer_token_account: Option<&'a InterfaceAccount<'info, TokenAccount>>,
+    agency_token_account: Option<&'a InterfaceAccount<'info, TokenAccount>>,
+    price_feed_config: &'a Option<Account<'info, PriceFeedConfig>>,
+    price_feed: &'a Option<UncheckedAccount<'info>>,
+    creator_token_account: &'a Option<InterfaceAccount<'info, TokenAccount>>,
+    token_mint: &'a InterfaceAccount<'info, Mint>,
+    token_program: &'a Interface<'info, TokenInterface>,
+    remaining_accounts: &'info [AccountInfo<'info>],
+}
+
+/// Settles a direct project campaign and marks it fulfilled. USD-priced
+/// campaigns are re-priced first (`creator` signs any top-up), then the escrow
+/// is split at the creator's fee rate with the KOL share routed into the
+/// vesting vault if the campaign vests, bundle legs are paid out and the
+/// creator's volume is recorded. Returns what the KOL and owner received.
+fn settle_direct_campaign<'info>(
+    payout: DirectPayout<'_, 'info>,
+    creator: Option<&Signer<'info>>,
+) -> Result<(u64, u64)> {
+    let DirectPayout {
+        marketplace_state,
+        campaign,
+        campaign_bump,
+        creator_profile,
+        campaign_token_account,
+        kol_token_account,
+        vesting,
+        vesting_token_account,
+        owner_token_account,
+        referrer_token_account,
+        agency_token_account,
+        price_feed_config,
+        price_feed,
+        creator_token_account,
+        token_mint,
+        token_program,
+        remaining_accounts,
+    } = payout;
+
+    settle_usd_price(
+        campaign,
+        campaign_bump,
+        price_feed_config,
+        price_feed,
+        campaign_token_account,
+        creator_token_account,
+        creator,
+        token_mint,
+        token_program,
+    )?;
+
+    let fee_bps = marketplace_state.platform_fee_bps(creator_profile, &campaign.token_mint);
+    let kol_destination =
+        vesting_destination(campaign, vesting, vesting_token_account, kol_token_account)?;
+    let (kol_amount, owner_amount) = pay_out_campaign(
+        campaign,
+        campaign_bump,
+        campaign_token_account,
+        kol_destination,
+        owner_token_account,
+        referrer_token_account,
+        agency_token_account,
+        campaign.amount_offered,
+        fee_bps,
+        token_mint,
+        token_mint.decimals,
+        token_program,
+    )?;
+    start_vesting(campaign, vesting, kol_amount)?;
+    pay_out_bundle_legs(
+        campaign,
+        campaign_bump,
+        marketplace_state,
+        creator_profile,
+        remaining_accounts,
+    )?;
+    creator_profile.record_settlement(campaign, campaign.amount_offered);
+
+    campaign.campaign_status = CampaignStatus::Fulfilled;
+
+    Ok((kol_amount, owner_amount))
+}
+
+/// Native counterpart of `settle_direct_campaign`: pays the lamport escrow out
+/// at the creator's fee rate, records the volume under the default pubkey and
+/// marks the campaign fulfilled.
+fn settle_native_campaign<'info>(
+    marketplace_state: &MarketplaceState,
+    campaign: &mut Account<'info, Campaign>,
+    creator_profile: &mut Account<'info, CreatorProfile>,
+    kol: &AccountInfo<'info>,
+    owner: &AccountInfo<'info>,
+    referrer: Option<&SystemAccount<'info>>,
+    agency: Option<&SystemAccount<'info>>,
+) -> Result<(u64, u64)> {
+    let fee_bps = marketplace_state.platform_fee_bps(creator_profile, &campaign.token_mint);
+    let (kol_amount, owner_amount) = pay_out_native_campaign(
+        campaign,
+        kol,
+        owner,
+        referrer,
+        agency,
+        campaign.amount_offered,
+        fee_bps,
+    )?;
+    creator_profile.record_settlement(campaign, campaign.amount_offered);
+
+    campaign.campaign_status = CampaignStatus::Fulfilled;
+
+    Ok((kol_amount, owner_amount))
+}
+
+/// KOL and marketplace owner shares of `total_amount` when the owner keeps `fee_bps`.
+fn split_payout_with_fee(total_amount: u64, fee_bps: u16) -> (u64, u64) {
+    // Calculate amounts based on percentages
+    let kol_amount = total_amount
+        .checked_mul(sol_cb::DIVIDER - fee_bps as u64)
+        .unwrap()
+        .checked_div(sol_cb::DIVIDER)
+        .unwrap();
+    let owner_amount = total_amount
+        .checked_mul(fee_bps as u64)
+        .unwrap()
+        .checked_div(sol_cb::DIVIDER)
+        .unwrap();
+
+    (kol_amount, owner_amount)
+}
+
+/// Fee split of `pay_out_campaign` at `fee_bps`, paid in lamports out of a
+/// native campaign. Rounding dust goes to the owner, and referrer and agency
+/// cuts come out of the owner and KOL shares the same way.
+fn pay_out_native_campaign<'info>(
+    campaign: &Account<'info, Campaign>,
+    kol: &AccountInfo<'info>,
+    owner: &AccountInfo<'info>,
+    referrer: Option<&SystemAccount<'info>>,
+    agency: Option<&SystemAccount<'info>>,
+    amount: u64,
+    fee_bps: u16,
+) -> Result<(u64, u64)> {
+    let (kol_amount, _) = split_payout_with_fee(amount, fee_bps);
+    let owner_amount = amount - kol_amount;
+
+    let referrer_amount = pay_native_revenue_share(
+        campaign,
+        referrer,
+        campaign.referrer,
+        campaign.referrer_share_bps,
+        owner_amount,
+    )?;
+    let agency_amount = pay_native_revenue_share(
+        campaign,
+        agency,
+        campaign.agency,
+        campaign.agency_share_bps,
+        kol_amount,
+    )?;
+    let kol_amount = kol_amount - agency_amount;
+    let owner_amount = owner_amount - referrer_amount;
+
+    transfer_lamports_from_campaign(campaign, kol, kol_amount)?;
+    transfer_lamports_from_campaign(campaign, owner, owner_amount)?;
+
+    Ok((kol_amount, owner_amount))
+}
+
+/// Lamport counterpart of `pay_revenue_share`: pays `share_bps` of
+/// `base_amount` to `recipient` out of a native campaign.
+fn pay_native_revenue_share<'info>(
+    campaign: &Account<'info, Campaign>,
+    recipient_account: Option<&SystemAccount<'info>>,
+    recipient: Pubkey,
+    share_bps: u16,
+    base_amount: u64,
+) -> Result<u64> {
+    if share_bps == 0 {
+        return Ok(0);
+    }
+
+    let recipient_account = match recipient_account {
+        Some(account) if account.key() == recipient => account,
+        _ => return err!(sol_cb::CustomErrorCode::InvalidRevenueShareAccount),
+    };
+
+    let amount = base_amount
+        .checked_mul(share_bps as u64)
+        .unwrap()
+        .checked_div(sol_cb::DIVIDER)
+        .unwrap();
+    transfer_lamports_from_campaign(campaign, &recipient_account.to_account_info(), amount)?;
+
+    Ok(amount)
+}
+
+/// Lamports escrowed in a native campaign, i.e. everything above its rent-exempt minimum.
+fn native_escrow_balance(campaign: &Account<Campaign>) -> Result<u64> {
+    let campaign_info = campaign.to_account_info();
+    let rent_exempt_minimum = Rent::get()?.minimum_balance(campaign_info.data_len());
+
+    Ok(campaign_info.lamports().saturating_sub(rent_exempt_minimum))
+}
+
+/// Moves `amount` lamports out of a native campaign. The PDA is owned by this
+/// program, so its balance can be debited directly.
+fn transfer_lamports_from_campaign<'info>(
+    campaign: &Account<'info, Campaign>,
+    to: &AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+
+    campaign.sub_lamports(amount)?;
+    to.add_lamports(amount)?;
+
+    Ok(())
+}
+
+/// Splits `amount` between the selected KOL and the marketplace owner at
+/// `fee_bps` and transfers both shares out of an escrow vault owned by the
+/// campaign. Rounding dust goes to the owner. A referrer's cut comes out of
+/// the owner share and an agency's cut out of the KOL share; the returned
+/// amounts are what the KOL and owner actually received.
+#[allow(clippy::too_many_arguments)]
+fn pay_out_campaign<'info>(
+    campaign: &Account<'info, Campaign>,
+    bump: u8,
+    campaign_token_account: &InterfaceAccount<'info, TokenAccount>,
+    kol_token_account: &InterfaceAccount<'info, TokenAccount>,
+    owner_token_account: &InterfaceAccount<'info, TokenAccount>,
+    referrer_token_account: Option<&InterfaceAccount<'info, TokenAccount>>,
+    agency_token_account: Option<&InterfaceAccount<'info, TokenAccount>>,
+    amount: u64,
+    fee_bps: u16,
+    token_mint: &InterfaceAccount<'info, Mint>,
+    decimals: u8,
+    token_program: &Interface<'info, TokenInterface>,
+) -> Result<(u64, u64)> {
+    // Escrowed amounts were netted against the transfer fee when recorded, and
+    // the fee may have changed by the time the creator funded the vault
+    if campaign_token_account.amount < amount {
+        return err!(sol_cb::CustomErrorCode::InsufficientFunds);
+    }
+
+    let (kol_amount, _) = split_payout_with_fee(amount, fee_bps);
+    let owner_amount = amount - kol_amount;
+
+    let referrer_amount = pay_revenue_share(
+        campaign,
+        bump,
+        campaign_token_account,
+        referrer_token_account,
+        campaign.referrer,
+        campaign.referrer_share_bps,
+        owner_amount,
+        token_mint,
+        decimals,
+        token_program,
+    )?;
+    let agency_amount = pay_revenue_share(
+        campaign,
+        bump,
+        campaign_token_account,
+        agency_token_account,
+        campaign.agency,
+        campaign.agency_share_bps,
+        kol_amount,
+        token_mint,
+        decimals,
+        token_program,
+    )?;
+    let kol_amount = kol_amount - agency_amount;
+    let owner_amount = owner_amount - referrer_amount;
+
+    // Transfer tokens to KOL (90%)
+    transfer_from_campaign(
+        campaign,
+        bump,
+        campaign_token_account,
+        kol_token_account,
+        token_mint,
+        decimals,
+        token_program,
+        kol_amount,
+    )?;
+
+    // Transfer tokens to Owner (10%)
+    transfer_from_campaign(
+        campaign,
+        bump,
+        campaign_token_account,
+        owner_token_account,
+        token_mint,
+        decimals,
+        token_program,
+        owner_amount,
+    )?;
+
+    Ok((kol_amount, owner_amount))
+}
+
+/// Pays every unsettled bundle leg out in full alongside the main escrow, at
+/// the creator's fee rate for the leg mint, and closes the emptied leg vaults.
+/// Legs go straight to the KOL even when the campaign vests; the vesting
+/// schedule only covers the main escrow.
+/// `remaining_accounts` holds the creator (who gets the vault rent back),
+/// then per unsettled leg in order: mint, leg vault, KOL token account, owner
+/// token account and token program, followed by referrer and agency token
+/// accounts when the campaign carries those shares.
+fn pay_out_bundle_legs<'info>(
+    campaign: &mut Account<'info, Campaign>,
+    bump: u8,
+    marketplace_state: &MarketplaceState,
+    creator_profile: &mut CreatorProfile,
+    remaining_accounts: &'info [AccountInfo<'info>],
+) -> Result<()> {
+    if campaign.bundle_legs.iter().all(|leg| leg.settled) {
+        return Ok(());
+    }
+
+    let mut accounts = remaining_accounts.iter();
+    let mut next_account = || {
+        accounts
+            .next()
+            .ok_or(error!(sol_cb::CustomErrorCode::BundleLegAccountsMissing))
+    };
+
+    let creator = next_account()?;
+    if creator.key() != campaign.creator_address {
+        return err!(sol_cb::CustomErrorCode::InvalidParameters);
+    }
+
+    // Copied out so the seeds don't hold a borrow of the campaign
+    let creator_address = campaign.creator_address;
+    let counter = campaign.counter.to_le_bytes();
+    let seeds = &[b"campaign", creator_address.as_ref(), &counter, &[bump]];
+    let signer_seeds = &[&seeds[..]];
+
+    for leg_index in 0..campaign.bundle_legs.len() {
+        let leg = campaign.bundle_legs[leg_index];
+        if leg.settled {
+            continue;
+        }
+
+        let token_mint = InterfaceAccount::<Mint>::try_from(next_account()?)?;
+        let leg_token_account = InterfaceAccount::<TokenAccount>::try_from(next_account()?)?;
+        let kol_token_account = InterfaceAccount::<TokenAccount>::try_from(next_account()?)?;
+        let owner_token_account = InterfaceAccount::<TokenAccount>::try_from(next_account()?)?;
+        let token_program = Interface::<TokenInterface>::try_from(next_account()?)?;
+        let referrer_token_account = match campaign.referrer_share_bps {
+            0 => None,
+            _ => Some(InterfaceAccount::<TokenAccount>::try_from(next_account()?)?),
+        };
+        let agency_token_account = match campaign.agency_share_bps {
+            0 => None,
+            _ => Some(InterfaceAccount::<TokenAccount>::try_from(next_account()?)?),
+        };
+
+        let leg_vault = get_associated_token_address_with_program_id(
+            &campaign.key(),
+            &leg.token_mint,
+            &token_program.key(),
+        );
+        if token_mint.key() != leg.token_mint
+            || leg_token_account.key() != leg_vault
+            || kol_token_account.owner != campaign.selected_kol
+            || kol_token_account.mint != leg.token_mint
+            || owner_token_account.owner != marketplace_state.owner
+            || owner_token_account.mint != leg.token_mint
+        {
+            return err!(sol_cb::CustomErrorCode::InvalidParameters);
+        }
+
+        let leg_amount = leg_token_account.amount;
+        let fee_bps = marketplace_state.platform_fee_bps(creator_profile, &leg.token_mint);
+        let (kol_amount, owner_amount) = pay_out_campaign(
+            campaign,
+            bump,
+            &leg_token_account,
+            &kol_token_account,
+            &owner_token_account,
+            referrer_token_account.as_ref(),
+            agency_token_account.as_ref(),
+            leg_amount,
+            fee_bps,
+            &token_mint,
+            token_mint.decimals,
+            &token_program,
+        )?;
+
+        harvest_withheld_fees(&leg_token_account, &token_mint, &token_program)?;
+        token_interface::close_account(CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            CloseAccount {
+                account: leg_token_account.to_account_info(),
+                destination: creator.to_account_info(),
+                authority: campaign.to_account_info(),
+            },
+            signer_seeds,
+        ))?;
+
+        creator_profile.record_volume(&creator_address, &leg.token_mint, leg_amount);
+        campaign.bundle_legs[leg_index].settled = true;
+
+        msg!(
+            "Paid bundle leg {} of campaign ID: {:?}: {} to KOL, {} to owner",
+            leg_index,
+            campaign.id,
+            kol_amount,
+            owner_amount
+        );
+    }
+
+    Ok(())
+}
+
+/// Pays `share_bps` of `base_amount` to `recipient` out of the campaign escrow
+/// and returns the amount paid.
+#[allow(clippy::too_many_arguments)]
+fn pay_revenue_share<'info>(
+    campaign: &Account<'info, Campaign>,
+    bump: u8,
+    campaign_token_account: &InterfaceAccount<'info, TokenAccount>,
+    recipient_token_account: Option<&InterfaceAccount<'info, TokenAccount>>,
+    recipient: Pubkey,
+    share_bps: u16,
+    base_amount: u64,
+    token_mint: &InterfaceAccount<'info, Mint>,
+    decimals: u8,
+    token_program: &Interface<'info, TokenInterface>,
+) -> Result<u64> {
+    let Some((recipient_token_account, amount)) = revenue_share(
+        recipient_token_account,
+        recipient,
+        share_bps,
+        base_amount,
+        &token_mint.key(),
+    )?
+    else {
+        return Ok(0);
+    };
+
+    transfer_from_campaign(
+        campaign,
+        bump,
+        campaign_token_account,
+        recipient_token_account,
+        token_mint,
+        decimals,
+        token_program,
+        amount,
+    )?;
+
+    Ok(amount)
+}
+
+/// `share_bps` of `base_amount` and the token account it is paid into, checked
+/// against `recipient`, or `None` when there is no share to pay.
+fn revenue_share<'a, 'info>(
+    recipient_token_account: Option<&'a InterfaceAccount<'info, TokenAccount>>,
+    recipient: Pubkey,
+    share_bps: u16,
+    base_amount: u64,
+    token_mint: &Pubkey,
+) -> Result<Option<(&'a InterfaceAccount<'info, TokenAccount>, u64)>> {
+    if share_bps == 0 {
+        return Ok(None);
+    }
+
+    let recipient_token_account = match recipient_token_account {
+        Some(token_account)
+            if token_account.owner == recipient && token_account.mint == *token_mint =>
+        {
+            token_account
+        }
+        _ => return err!(sol_cb::CustomErrorCode::InvalidRevenueShareAccount),
+    };
+
+    let amount = base_amount
+        .checked_mul(share_bps as u64)
+        .unwrap()
+        .checked_div(sol_cb::DIVIDER)
+        .unwrap();
+
+    Ok(Some((recipient_token_account, amount)))
+}
+
+/// Moves `amount` out of the campaign escrow, signing with the campaign PDA.
+#[allow(clippy::too_many_arguments)]
+fn transfer_from_campaign<'info>(
+    campaign: &Account<'info, Campaign>,
+    bump: u8,
+    campaign_token_account: &InterfaceAccount<'info, TokenAccount>,
+    to: &InterfaceAccount<'info, TokenAccount>,
+    token_mint: &InterfaceAccount<'info, Mint>,
+    decimals: u8,
+    token_program: &Interface<'info, TokenInterface>,
+    amount: u64,
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+
+    let seeds = &[
+        b"campaign",
+        campaign.creator_address.as_ref(),
+        &campaign.counter.to_le_bytes(),
+        &[bump],
+    ];
+    let signer_seeds = &[&seeds[..]];
+
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            TransferChecked {
+                from: campaign_token_account.to_account_info(),
+                mint: token_mint.to_account_info(),
+                to: to.to_account_info(),
+                authority: campaign.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount,
+        decimals,
+    )
+}
+
+/// Moves `amount` out of the subscription vault, signing with the subscription PDA.
+#[allow(clippy::too_many_arguments)]
+fn transfer_from_subscription<'info>(
+    subscription: &Account<'info, Subscription>,
+    bump: u8,
+    subscription_token_account: &InterfaceAccount<'info, TokenAccount>,
+    to: &InterfaceAccount<'info, TokenAccount>,
+    token_mint: &InterfaceAccount<'info, Mint>,
+    decimals: u8,
+    token_program: &Interface<'info, TokenInterface>,
+    amount: u64,
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+
+    let seeds = &[
+        b"subscription",
+        subscription.creator_address.as_ref(),
+        &subscription.counter.to_le_bytes(),
+        &[bump],
+    ];
+    let signer_seeds = &[&seeds[..]];
+
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            TransferChecked {
+                from: subscription_token_account.to_account_info(),
+                mint: token_mint.to_account_info(),
+                to: to.to_account_info(),
+                authority: subscription.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount,
+        decimals,
+    )
+}
+
+/// Moves `amount` out of the open campaign vault, signing with the open campaign PDA.
+#[allow(clippy::too_many_arguments)]
+fn transfer_from_open_campaign<'info>(
+    open_campaign: &Account<'info, OpenCampaign>,
+    bump: u8,
+    campaign_token_account: &InterfaceAccount<'info, TokenAccount>,
+    to: &InterfaceAccount<'info, TokenAccount>,
+    token_mint: &InterfaceAccount<'info, Mint>,
+    decimals: u8,
+    token_program: &Interface<'info, TokenInterface>,
+    amount: u64,
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+
+    let seeds = &[
+        b"open_campaign",
+        open_campaign.creator_address.as_ref(),
+        &open_campaign.counter.to_le_bytes(),
+        &[bump],
+    ];
+    let signer_seeds = &[&seeds[..]];
+
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            TransferChecked {
+                from: campaign_token_account.to_account_info(),
+                mint: token_mint.to_account_info(),
+                to: to.to_account_info(),
+                authority: open_campaign.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount,
+        decimals,
+    )
+}
+
+/// Rejects Token-2022 mints carrying extensions that would let tokens be
+/// frozen, clawed back or routed through foreign programs while in escrow.
+/// Interest-bearing and transfer-fee mints are fine since raw amounts are
+/// unaffected or accounted for at funding time.
+fn check_mint_extensions(token_mint: &InterfaceAccount<Mint>) -> Result<()> {
+    let mint_info = token_mint.to_account_info();
+    if *mint_info.owner != spl_token_2022::ID {
+        return Ok(());
+    }
+
+    let mint_data = mint_info.try_borrow_data()?;
+    let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+    for extension in mint.get_extension_types()? {
+        if matches!(
+            extension,
+            ExtensionType::NonTransferable
+                | ExtensionType::PermanentDelegate
+                | ExtensionType::TransferHook
+                | ExtensionType::DefaultAccountState
+                | ExtensionType::ConfidentialTransferMint
+        ) {
+            msg!("Unsupported mint extension: {:?}", extension);
+            return err!(sol_cb::CustomErrorCode::UnsupportedMintExtension);
+        }
+    }
+
+    Ok(())
+}
+
+/// Moves any Token-2022 transfer fees withheld in an escrow vault into the
+/// mint. A vault still holding withheld fees cannot be closed, and harvesting
+/// is permissionless so no signer is needed.
+fn harvest_withheld_fees<'info>(
+    vault: &InterfaceAccount<'info, TokenAccount>,
+    token_mint: &InterfaceAccount<'info, Mint>,
+    token_program: &Interface<'info, TokenInterface>,
+) -> Result<()> {
+    let vault_info = vault.to_account_info();
+    if *vault_info.owner != spl_token_2022::ID {
+        return Ok(());
+    }
+
+    let withheld_amount = {
+        let vault_data = vault_info.try_borrow_data()?;
+        let account = StateWithExtensions::<spl_token_2022::state::Account>::unpack(&vault_data)?;
+        match account.get_extension::<TransferFeeAmount>() {
+            Ok(transfer_fee_amount) => u64::from(transfer_fee_amount.withheld_amount),
+            Err(_) => 0,
+        }
+    };
+    if withheld_amount == 0 {
+        return Ok(());
+    }
+
+    transfer_fee::harvest_withheld_tokens_to_mint(
+        CpiContext::new(
+            token_program.to_account_info(),
+            HarvestWithheldTokensToMint {
+                token_program_id: token_program.to_account_info(),
+                mint: token_mint.to_account_info(),
+            },
+        ),
+        vec![vault_info],
+    )
+}
+
+/// What the escrow actually receives when `amount` is sent to it, after any
+/// Token-2022 transfer fee for the current epoch.
+fn net_of_transfer_fee(token_mint: &InterfaceAccount<Mint>, amount: u64) -> Result<u64> {
+    let mint_info = token_mint.to_account_info();
+    if *mint_info.owner != spl_token_2022::ID {
+        return Ok(amount);
+    }
+
+    let mint_data = mint_info.try_borrow_data()?;
+    let mint = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_data)?;
+    let fee = match mint.get_extension::<TransferFeeConfig>() {
+        Ok(transfer_fee_config) => transfer_fee_config
+            .calculate_epoch_fee(Clock::get()?.epoch, amount)
+            .unwrap(),
+        Err(_) => 0,
+    };
+
+    Ok(amount.checked_sub(fee).unwrap())
+}
+
+#[derive(Accounts)]
+pub struct InitializeMarketplace<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    #[account(
+        init,
+        payer = owner,
+        space = MarketplaceState::INIT_SPACE,
+        seeds = [b"marketplace"],
+        bump,
+    )]
+    pub marketplace_state: Account<'info, MarketplaceState>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CreateNewCampaign<'info> {
+    #[account(
+        mut,
+        seeds = [b"marketplace"],
+        bump,
+    )]
+    pub marketplace_state: Account<'info, MarketplaceState>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    #[account(
+        constraint = marketplace_state.allowed_tokens.contains(&token_mint.key())
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        init,
+        payer = creator,
+        space = Campaign::INIT_SPACE,
+        seeds = [b"campaign", creator.key().as_ref(), &marketplace_state.campaign_counter.to_le_bytes()],
+        bump,
+    )]
+    pub campaign: Account<'info, Campaign>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateCampaign<'info> {
+    #[account(
+        mut,
+        seeds = [b"marketplace"],
+        bump,
+    )]
+    pub marketplace_state: Account<'info, MarketplaceState>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"campaign", creator.key().as_ref(), &campaign.counter.to_le_bytes()],
+        bump,
+        constraint = campaign.creator_address == creator.key() @ CustomErrorCode::Unauthorized,
+        constraint = !campaign.is_native @ CustomErrorCode::EscrowAssetMismatch
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(mut,
+        constraint = token_mint.key() == campaign.token_mint
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptProjectCampaign<'info> {
+    #[account(
+        mut,
+        seeds = [b"marketplace"],
+        bump,
+    )]
+    pub marketplace_state: Account<'info, MarketplaceState>,
+    #[account(mut)]
+    pub kol: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator_address.as_ref(), &campaign.counter.to_le_bytes()],
+        bump,
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    // Only needed when posting a bond
+    #[account(mut)]
+    pub kol_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut)]
+    pub bond_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub token_mint: Option<InterfaceAccount<'info, Mint>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+}
+
+#[derive(Accounts)]
+pub struct SubmitDeliverable<'info> {
+    #[account(
+        seeds = [b"marketplace"],
+        bump,
+    )]
+    pub marketplace_state: Account<'info, MarketplaceState>,
+    #[account(mut)]
+    pub kol: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator_address.as_ref(), &campaign.counter.to_le_bytes()],
+        bump,
+        constraint = campaign.selected_kol == kol.key() @ CustomErrorCode::Unauthorized
+    )]
+    pub campaign: Account<'info, Campaign>,
+}
+
+#[derive(Accounts)]
+pub struct DiscardProjectCampaign<'info> {
+    #[account(
+        mut,
+        seeds = [b"marketplace"],
+        bump,
+    )]
+    pub marketplace_state: Account<'info, MarketplaceState>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator_address.as_ref(), &campaign.counter.to_le_bytes()],
+        bump,
+        constraint = !campaign.is_native @ CustomErrorCode::EscrowAssetMismatch
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = campaign,
+        associated_token::token_program = token_program
+    )]
+    pub campaign_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = creator_token_account.owner == campaign.creator_address,
+        constraint = creator_token_account.mint == campaign.token_mint
+    )]
+    pub creator_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = kol_token_account.owner == campaign.selected_kol,
+        constraint = kol_token_account.mint == campaign.token_mint
+    )]
+    pub kol_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = token_mint.key() == campaign.token_mint
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct FulfilProjectCampaign<'info> {
+    #[account(
+        mut,
+        seeds = [b"marketplace"],
+        bump,
+    )]
+    pub marketplace_state: Account<'info, MarketplaceState>,
+
+    #[account(
+        mut,
+        constraint = owner.key() == marketplace_state.owner @ CustomErrorCode::Unauthorized
+    )]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator_address.as_ref(), &campaign.counter.to_le_bytes()],
+        bump,
+        constraint = !campaign.is_native @ CustomErrorCode::EscrowAssetMismatch
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = CreatorProfile::INIT_SPACE,
+        seeds = [b"creator_profile", campaign.creator_address.as_ref()],
+        bump,
+    )]
+    pub creator_profile: Account<'info, CreatorProfile>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = campaign,
+        associated_token::token_program = token_program
+    )]
+    pub campaign_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = kol_token_account.owner == campaign.selected_kol,
+        constraint = kol_token_account.mint == campaign.token_mint
+    )]
+    pub kol_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"vesting", campaign.key().as_ref()],
+        bump,
+    )]
+    pub vesting: Option<Account<'info, Vesting>>,
+
+    #[account(mut)]
+    pub vesting_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub referrer_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub agency_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [b"price_feed", campaign.token_mint.as_ref()],
+        bump,
+    )]
+    pub price_feed_config: Option<Account<'info, PriceFeedConfig>>,
+
+    /// CHECK: Must match the price feed config; the layout is validated when
+    /// the price is read.
+    pub price_feed: Option<UncheckedAccount<'info>>,
+
+    #[account(
+        mut,
+        constraint = creator_token_account.owner == campaign.creator_address,
+        constraint = creator_token_account.mint == campaign.token_mint
+    )]
+    pub creator_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = owner_token_account.owner == marketplace_state.owner,
+        constraint = owner_token_account.mint == campaign.token_mint
+    )]
+    pub owner_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = token_mint.key() == campaign.token_mint
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ApproveDeliverable<'info> {
+    #[account(
+        mut,
+        seeds = [b"marketplace"],
+        bump,
+    )]
+    pub marketplace_state: Account<'info, MarketplaceState>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator_address.as_ref(), &campaign.counter.to_le_bytes()],
+        bump,
+        constraint = !campaign.is_native @ CustomErrorCode::EscrowAssetMismatch
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(
+        init_if_needed,
+        payer = creator,
+        space = CreatorProfile::INIT_SPACE,
+        seeds = [b"creator_profile", campaign.creator_address.as_ref()],
+        bump,
+    )]
+    pub creator_profile: Account<'info, CreatorProfile>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = campaign,
+        associated_token::token_program = token_program
+    )]
+    pub campaign_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = kol_token_account.owner == campaign.selected_kol,
+        constraint = kol_token_account.mint == campaign.token_mint
+    )]
+    pub kol_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"vesting", campaign.key().as_ref()],
+        bump,
+    )]
+    pub vesting: Option<Account<'info, Vesting>>,
+
+    #[account(mut)]
+    pub vesting_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub referrer_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub agency_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [b"price_feed", campaign.token_mint.as_ref()],
+        bump,
+    )]
+    pub price_feed_config: Option<Account<'info, PriceFeedConfig>>,
+
+    /// CHECK: Must match the price feed config; the layout is validated when
+    /// the price is read.
+    pub price_feed: Option<UncheckedAccount<'info>>,
+
+    #[account(
+        mut,
+        constraint = creator_token_account.owner == campaign.creator_address,
+        constraint = creator_token_account.mint == campaign.token_mint
+    )]
+    pub creator_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = owner_token_account.owner == marketplace_state.owner,
+        constraint = owner_token_account.mint == campaign.token_mint
+    )]
+    pub owner_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = token_mint.key() == campaign.token_mint
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ReleasePayout<'info> {
+    #[account(
+        mut,
+        seeds = [b"marketplace"],
+        bump,
+    )]
+    pub marketplace_state: Account<'info, MarketplaceState>,
+
+    #[account(mut)]
+    pub caller: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator_address.as_ref(), &campaign.counter.to_le_bytes()],
+        bump,
+        constraint = !campaign.is_native @ CustomErrorCode::EscrowAssetMismatch
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(
+        init_if_needed,
+        payer = caller,
+        space = CreatorProfile::INIT_SPACE,
+        seeds = [b"creator_profile", campaign.creator_address.as_ref()],
+        bump,
+    )]
+    pub creator_profile: Account<'info, CreatorProfile>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = campaign,
+        associated_token::token_program = token_program
+    )]
+    pub campaign_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = kol_token_account.owner == campaign.selected_kol,
+        constraint = kol_token_account.mint == campaign.token_mint
+    )]
+    pub kol_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"vesting", campaign.key().as_ref()],
+        bump,
+    )]
+    pub vesting: Option<Account<'info, Vesting>>,
+
+    #[account(mut)]
+    pub vesting_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub referrer_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub agency_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [b"price_feed", campaign.token_mint.as_ref()],
+        bump,
+    )]
+    pub price_feed_config: Option<Account<'info, PriceFeedConfig>>,
+
+    /// CHECK: Must match the price feed config; the layout is validated when
+    /// the price is read.
+    pub price_feed: Option<UncheckedAccount<'info>>,
+
+    #[account(
+        mut,
+        constraint = creator_token_account.owner == campaign.creator_address,
+        constraint = creator_token_account.mint == campaign.token_mint
+    )]
+    pub creator_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = owner_token_account.owner == marketplace_state.owner,
+        constraint = owner_token_account.mint == campaign.token_mint
+    )]
+    pub owner_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = token_mint.key() == campaign.token_mint
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct OpenDispute<'info> {
+    #[account(mut)]
+    pub party: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator_address.as_ref(), &campaign.counter.to_le_bytes()],
+        bump,
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(
+        init,
+        payer = party,
+        space = Dispute::INIT_SPACE,
+        seeds = [b"dispute", campaign.key().as_ref()],
+        bump,
+    )]
+    pub dispute: Account<'info, Dispute>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SubmitDisputeEvidence<'info> {
+    pub party: Signer<'info>,
+
+    #[account(
+        seeds = [b"campaign", campaign.creator_address.as_ref(), &campaign.counter.to_le_bytes()],
+        bump,
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(
+        mut,
+        seeds = [b"dispute", campaign.key().as_ref()],
+        bump,
+    )]
+    pub dispute: Account<'info, Dispute>,
+}
+
+#[derive(Accounts)]
+pub struct ResolveDispute<'info> {
+    #[account(
+        seeds = [b"marketplace"],
+        bump,
+    )]
+    pub marketplace_state: Account<'info, MarketplaceState>,
+
+    #[account(
+        mut,
+        constraint = arbiter.key() == marketplace_state.arbiter @ CustomErrorCode::Unauthorized
+    )]
+    pub arbiter: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator_address.as_ref(), &campaign.counter.to_le_bytes()],
+        bump,
+        constraint = !campaign.is_native @ CustomErrorCode::EscrowAssetMismatch
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(
+        init_if_needed,
+        payer = arbiter,
+        space = CreatorProfile::INIT_SPACE,
+        seeds = [b"creator_profile", campaign.creator_address.as_ref()],
+        bump,
+    )]
+    pub creator_profile: Account<'info, CreatorProfile>,
+
+    #[account(
+        mut,
+        seeds = [b"dispute", campaign.key().as_ref()],
+        bump,
+    )]
+    pub dispute: Account<'info, Dispute>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = campaign,
+        associated_token::token_program = token_program
+    )]
+    pub campaign_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = kol_token_account.owner == campaign.selected_kol,
+        constraint = kol_token_account.mint == campaign.token_mint
+    )]
+    pub kol_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"vesting", campaign.key().as_ref()],
+        bump,
+    )]
+    pub vesting: Option<Account<'info, Vesting>>,
+
+    #[account(mut)]
+    pub vesting_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub referrer_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub agency_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = creator_token_account.owner == campaign.creator_address,
+        constraint = creator_token_account.mint == campaign.token_mint
+    )]
+    pub creator_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = owner_token_account.owner == marketplace_state.owner,
+        constraint = owner_token_account.mint == campaign.token_mint
+    )]
+    pub owner_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        constraint = token_mint.key() == campaign.token_mint
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct PriceCampaignInUsd<'info> {
+    #[account(
+        seeds = [b"marketplace"],
+        bump,
+    )]
+    pub marketplace_state: Account<'info, MarketplaceState>,
+
+    pub creator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator_address.as_ref(), &campaign.counter.to_le_bytes()],
+        bump,
+        constraint = campaign.creator_address == creator.key() @ CustomErrorCode::Unauthorized,
+        constraint = !campaign.is_native @ CustomErrorCode::NativeCampaignUnsupported
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(
+        seeds = [b"price_feed", campaign.token_mint.as_ref()],
+        bump,
+    )]
+    pub price_feed_config: Account<'info, PriceFeedConfig>,
+
+    /// CHECK: Address is pinned by the admin-set price feed config and the
+    /// layout is validated when the price is read.
+    #[account(address = price_feed_config.price_feed @ CustomErrorCode::InvalidPriceFeed)]
+    pub price_feed: UncheckedAccount<'info>,
+
+    #[account(
+        constraint = token_mint.key() == campaign.token_mint
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+}
+
+#[derive(Accounts)]
+pub struct SetCampaignVesting<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator_address.as_ref(), &campaign.counter.to_le_bytes()],
+        bump,
+        constraint = campaign.creator_address == creator.key() @ CustomErrorCode::Unauthorized,
+        constraint = !campaign.is_native @ CustomErrorCode::NativeCampaignUnsupported
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(
+        init_if_needed,
+        payer = creator,
+        space = Vesting::INIT_SPACE,
+        seeds = [b"vesting", campaign.key().as_ref()],
+        bump,
+    )]
+    pub vesting: Account<'info, Vesting>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimVestedTokens<'info> {
+    #[account(
+        seeds = [b"marketplace"],
+        bump,
+    )]
+    pub marketplace_state: Account<'info, MarketplaceState>,
+
+    pub kol: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vesting", vesting.campaign.as_ref()],
+        bump,
+        constraint = vesting.kol == kol.key() @ CustomErrorCode::Unauthorized
+    )]
+    pub vesting: Account<'info, Vesting>,
+
+    /// CHECK: Receives the rent of the vesting account and its vault after the
+    /// final claim; pinned to the creator who funded them.
+    #[account(
+        mut,
+        address = vesting.creator @ CustomErrorCode::Unauthorized
+    )]
+    pub creator: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = vesting,
+        associated_token::token_program = token_program
+    )]
+    pub vesting_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = kol_token_account.owner == kol.key(),
+        constraint = kol_token_account.mint == vesting.token_mint
+    )]
+    pub kol_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = token_mint.key() == vesting.token_mint
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct MarkCampaignUnfulfilled<'info> {
+    #[account(
+        seeds = [b"marketplace"],
+        bump,
+    )]
+    pub marketplace_state: Account<'info, MarketplaceState>,
+
+    pub caller: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator_address.as_ref(), &campaign.counter.to_le_bytes()],
+        bump,
+        constraint = !campaign.is_native @ CustomErrorCode::EscrowAssetMismatch
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = campaign,
+        associated_token::token_program = token_program
+    )]
+    pub campaign_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = creator_token_account.owner == campaign.creator_address,
+        constraint = creator_token_account.mint == campaign.token_mint
+    )]
+    pub creator_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        constraint = token_mint.key() == campaign.token_mint
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct SettleCampaignBond<'info> {
+    #[account(
+        seeds = [b"marketplace"],
+        bump,
+    )]
+    pub marketplace_state: Account<'info, MarketplaceState>,
+
+    pub caller: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator_address.as_ref(), &campaign.counter.to_le_bytes()],
+        bump,
+        constraint = !campaign.is_native @ CustomErrorCode::EscrowAssetMismatch
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    // Required when the campaign was resolved by the arbiter
+    #[account(
+        seeds = [b"dispute", campaign.key().as_ref()],
+        bump,
+    )]
+    pub dispute: Option<Account<'info, Dispute>>,
+
+    /// CHECK: PDA that owns the bond vault, only used as a signer
+    #[account(
+        seeds = [b"bond", campaign.key().as_ref()],
+        bump,
+    )]
+    pub bond_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = bond_authority,
+        associated_token::token_program = token_program
+    )]
+    pub bond_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = kol_token_account.owner == campaign.selected_kol,
+        constraint = kol_token_account.mint == campaign.token_mint
+    )]
+    pub kol_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = owner_token_account.owner == marketplace_state.owner,
+        constraint = owner_token_account.mint == campaign.token_mint
+    )]
+    pub owner_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = creator_token_account.owner == campaign.creator_address,
+        constraint = creator_token_account.mint == campaign.token_mint
+    )]
+    pub creator_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut, address = campaign.selected_kol)]
+    pub kol: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = token_mint.key() == campaign.token_mint
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct SetCampaignReferrer<'info> {
+    #[account(
+        seeds = [b"marketplace"],
+        bump,
+    )]
+    pub marketplace_state: Account<'info, MarketplaceState>,
+
+    // Referrers are paid out of the platform fee, so only the owner approves them
+    #[account(
+        constraint = owner.key() == marketplace_state.owner @ CustomErrorCode::Unauthorized
+    )]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator_address.as_ref(), &campaign.counter.to_le_bytes()],
+        bump,
+    )]
+    pub campaign: Account<'info, Campaign>,
+}
+
+#[derive(Accounts)]
+pub struct SetCampaignAgency<'info> {
+    #[account(
+        seeds = [b"marketplace"],
+        bump,
+    )]
+    pub marketplace_state: Account<'info, MarketplaceState>,
+
+    pub kol: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator_address.as_ref(), &campaign.counter.to_le_bytes()],
+        bump,
+        constraint = campaign.selected_kol == kol.key() @ CustomErrorCode::Unauthorized
+    )]
+    pub campaign: Account<'info, Campaign>,
+}
+
+#[derive(Accounts)]
+pub struct AddCampaignBundleLeg<'info> {
+    #[account(
+        seeds = [b"marketplace"],
+        bump,
+    )]
+    pub marketplace_state: Account<'info, MarketplaceState>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator_address.as_ref(), &campaign.counter.to_le_bytes()],
+        bump,
+        constraint = campaign.creator_address == creator.key() @ CustomErrorCode::Unauthorized,
+        constraint = !campaign.is_native @ CustomErrorCode::NativeCampaignUnsupported
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(
+        constraint = marketplace_state.allowed_tokens.contains(&token_mint.key())
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = campaign,
+        associated_token::token_program = token_program,
+    )]
+    pub leg_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = creator_token_account.owner == creator.key(),
+        constraint = creator_token_account.mint == token_mint.key()
+    )]
+    pub creator_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct SettleCampaignBundleLeg<'info> {
+    #[account(
+        seeds = [b"marketplace"],
+        bump,
+    )]
+    pub marketplace_state: Account<'info, MarketplaceState>,
+
+    #[account(mut)]
+    pub caller: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator_address.as_ref(), &campaign.counter.to_le_bytes()],
+        bump,
+        constraint = !campaign.is_native @ CustomErrorCode::EscrowAssetMismatch
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(
+        seeds = [b"dispute", campaign.key().as_ref()],
+        bump,
+    )]
+    pub dispute: Option<Account<'info, Dispute>>,
+
+    #[account(
+        init_if_needed,
+        payer = caller,
+        space = CreatorProfile::INIT_SPACE,
+        seeds = [b"creator_profile", campaign.creator_address.as_ref()],
+        bump,
+    )]
+    pub creator_profile: Account<'info, CreatorProfile>,
+
+    #[account(mut)]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = campaign,
+        associated_token::token_program = token_program,
+    )]
+    pub leg_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = kol_token_account.owner == campaign.selected_kol,
+        constraint = kol_token_account.mint == token_mint.key()
+    )]
+    pub kol_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = owner_token_account.owner == marketplace_state.owner,
+        constraint = owner_token_account.mint == token_mint.key()
+    )]
+    pub owner_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub referrer_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub agency_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = creator_token_account.owner == campaign.creator_address,
+        constraint = creator_token_account.mint == token_mint.key()
+    )]
+    pub creator_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        address = campaign.creator_address
+    )]
+    pub creator: SystemAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CreateNativeCampaign<'info> {
+    #[account(
+        mut,
+        seeds = [b"marketplace"],
+        bump,
+    )]
+    pub marketplace_state: Account<'info, MarketplaceState>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    #[account(
+        init,
+        payer = creator,
+        space = Campaign::INIT_SPACE,
+        seeds = [b"campaign", creator.key().as_ref(), &marketplace_state.campaign_counter.to_le_bytes()],
+        bump,
+    )]
+    pub campaign: Account<'info, Campaign>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DiscardNativeCampaign<'info> {
+    #[account(
+        mut,
+        constraint = creator.key() == campaign.creator_address @ CustomErrorCode::Unauthorized
+    )]
+    pub creator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator_address.as_ref(), &campaign.counter.to_le_bytes()],
+        bump,
+        constraint = campaign.is_native @ CustomErrorCode::EscrowAssetMismatch
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(
+        mut,
+        address = campaign.selected_kol
+    )]
+    pub kol: SystemAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct FulfilNativeCampaign<'info> {
+    #[account(
+        seeds = [b"marketplace"],
+        bump,
+    )]
+    pub marketplace_state: Account<'info, MarketplaceState>,
+
+    #[account(
+        mut,
+        constraint = owner.key() == marketplace_state.owner @ CustomErrorCode::Unauthorized
+    )]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator_address.as_ref(), &campaign.counter.to_le_bytes()],
+        bump,
+        constraint = campaign.is_native @ CustomErrorCode::EscrowAssetMismatch
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = CreatorProfile::INIT_SPACE,
+        seeds = [b"creator_profile", campaign.creator_address.as_ref()],
+        bump,
+    )]
+    pub creator_profile: Account<'info, CreatorProfile>,
+
+    #[account(
+        mut,
+        address = campaign.selected_kol
+    )]
+    pub kol: SystemAccount<'info>,
+
+    // Only checked and paid when the campaign carries a referrer or agency share
+    #[account(mut)]
+    pub referrer: Option<SystemAccount<'info>>,
+
+    #[account(mut)]
+    pub agency: Option<SystemAccount<'info>>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ApproveNativeDeliverable<'info> {
+    #[account(
+        seeds = [b"marketplace"],
+        bump,
+    )]
+    pub marketplace_state: Account<'info, MarketplaceState>,
+
+    #[account(
+        mut,
+        constraint = creator.key() == campaign.creator_address @ CustomErrorCode::Unauthorized
+    )]
+    pub creator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator_address.as_ref(), &campaign.counter.to_le_bytes()],
+        bump,
+        constraint = campaign.is_native @ CustomErrorCode::EscrowAssetMismatch
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(
+        init_if_needed,
+        payer = creator,
+        space = CreatorProfile::INIT_SPACE,
+        seeds = [b"creator_profile", campaign.creator_address.as_ref()],
+        bump,
+    )]
+    pub creator_profile: Account<'info, CreatorProfile>,
+
+    #[account(
+        mut,
+        address = campaign.selected_kol
+    )]
+    pub kol: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        address = marketplace_state.owner
+    )]
+    pub owner: SystemAccount<'info>,
+
+    // Only checked and paid when the campaign carries a referrer or agency share
+    #[account(mut)]
+    pub referrer: Option<SystemAccount<'info>>,
+
+    #[account(mut)]
+    pub agency: Option<SystemAccount<'info>>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ReleaseNativePayout<'info> {
+    #[account(
+        seeds = [b"marketplace"],
+        bump,
+    )]
+    pub marketplace_state: Account<'info, MarketplaceState>,
+
+    #[account(mut)]
+    pub caller: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator_address.as_ref(), &campaign.counter.to_le_bytes()],
+        bump,
+        constraint = campaign.is_native @ CustomErrorCode::EscrowAssetMismatch
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(
+        init_if_needed,
+        payer = caller,
+        space = CreatorProfile::INIT_SPACE,
+        seeds = [b"creator_profile", campaign.creator_address.as_ref()],
+        bump,
+    )]
+    pub creator_profile: Account<'info, CreatorProfile>,
+
+    #[account(
+        mut,
+        address = campaign.selected_kol
+    )]
+    pub kol: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        address = marketplace_state.owner
+    )]
+    pub owner: SystemAccount<'info>,
+
+    // Only checked and paid when the campaign carries a referrer or agency share
+    #[account(mut)]
+    pub referrer: Option<SystemAccount<'info>>,
+
+    #[account(mut)]
+    pub agency: Option<SystemAccount<'info>>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ResolveNativeDispute<'info> {
+    #[account(
+        seeds = [b"marketplace"],
+        bump,
+    )]
+    pub marketplace_state: Account<'info, MarketplaceState>,
+
+    #[account(
+        mut,
+        constraint = arbiter.key() == marketplace_state.arbiter @ CustomErrorCode::Unauthorized
+    )]
+    pub arbiter: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator_address.as_ref(), &campaign.counter.to_le_bytes()],
+        bump,
+        constraint = campaign.is_native @ CustomErrorCode::EscrowAssetMismatch
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(
+        init_if_needed,
+        payer = arbiter,
+        space = CreatorProfile::INIT_SPACE,
+        seeds = [b"creator_profile", campaign.creator_address.as_ref()],
+        bump,
+    )]
+    pub creator_profile: Account<'info, CreatorProfile>,
+
+    #[account(
+        mut,
+        seeds = [b"dispute", campaign.key().as_ref()],
+        bump,
+    )]
+    pub dispute: Account<'info, Dispute>,
+
+    #[account(
+        mut,
+        address = campaign.selected_kol
+    )]
+    pub kol: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        address = marketplace_state.owner
+    )]
+    pub owner: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        address = campaign.creator_address
+    )]
+    pub creator: SystemAccount<'info>,
+
+    // Only checked and paid when the campaign carries a referrer or agency share
+    #[account(mut)]
+    pub referrer: Option<SystemAccount<'info>>,
+
+    #[account(mut)]
+    pub agency: Option<SystemAccount<'info>>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MarkNativeCampaignUnfulfilled<'info> {
+    pub caller: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator_address.as_ref(), &campaign.counter.to_le_bytes()],
+        bump,
+        constraint = campaign.is_native @ CustomErrorCode::EscrowAssetMismatch
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(
+        mut,
+        address = campaign.creator_address
+    )]
+    pub creator: SystemAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseNativeCampaign<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator_address.as_ref(), &campaign.counter.to_le_bytes()],
+        bump,
+        close = creator,
+        constraint = campaign.creator_address == creator.key() @ CustomErrorCode::Unauthorized,
+        constraint = campaign.is_native @ CustomErrorCode::EscrowAssetMismatch
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    // Required when the campaign was resolved by the arbiter
+    #[account(
+        mut,
+        seeds = [b"dispute", campaign.key().as_ref()],
+        bump,
+    )]
+    pub dispute: Option<Account<'info, Dispute>>,
+
+    /// CHECK: Receives the dispute rent, checked against `dispute.opened_by`
+    #[account(mut)]
+    pub dispute_opener: Option<UncheckedAccount<'info>>,
+}
+
+#[derive(Accounts)]
+pub struct CreateOpenCampaign<'info> {
+    #[account(
+        mut,
+        seeds = [b"marketplace"],
+        bump,
+    )]
+    pub marketplace_state: Account<'info, MarketplaceState>,
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    #[account(
+        constraint = marketplace_state.allowed_tokens.contains(&token_mint.key())
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        init,
+        payer = creator,
+        space = OpenCampaign::INIT_SPACE,
+        seeds = [b"open_campaign", creator.key().as_ref(), &marketplace_state.campaign_counter.to_le_bytes()],
+        bump,
+    )]
+    pub open_campaign: Account<'info, OpenCampaign>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct TopUpOpenCampaign<'info> {
+    #[account(
+        seeds = [b"marketplace"],
+        bump,
+    )]
+    pub marketplace_state: Account<'info, MarketplaceState>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"open_campaign", open_campaign.creator_address.as_ref(), &open_campaign.counter.to_le_bytes()],
+        bump,
+        constraint = open_campaign.creator_address == creator.key() @ CustomErrorCode::Unauthorized
+    )]
+    pub open_campaign: Account<'info, OpenCampaign>,
+
+    #[account(
+        mut,
+        constraint = campaign_token_account.owner == open_campaign.key(),
+        constraint = campaign_token_account.mint == open_campaign.token_mint
+    )]
+    pub campaign_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = creator_token_account.owner == creator.key(),
+        constraint = creator_token_account.mint == open_campaign.token_mint
+    )]
+    pub creator_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        constraint = token_mint.key() == open_campaign.token_mint
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct ExtendOpenCampaign<'info> {
+    #[account(
+        seeds = [b"marketplace"],
+        bump,
+    )]
+    pub marketplace_state: Account<'info, MarketplaceState>,
+
+    pub creator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"open_campaign", open_campaign.creator_address.as_ref(), &open_campaign.counter.to_le_bytes()],
+        bump,
+        constraint = open_campaign.creator_address == creator.key() @ CustomErrorCode::Unauthorized
+    )]
+    pub open_campaign: Account<'info, OpenCampaign>,
+}
+
+#[derive(Accounts)]
+pub struct JoinOpenCampaign<'info> {
+    #[account(mut)]
+    pub kol: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"open_campaign", open_campaign.creator_address.as_ref(), &open_campaign.counter.to_le_bytes()],
+        bump,
+    )]
+    pub open_campaign: Account<'info, OpenCampaign>,
+
+    #[account(
+        init,
+        payer = kol,
+        space = Participation::INIT_SPACE,
+        seeds = [b"participation", open_campaign.key().as_ref(), kol.key().as_ref()],
+        bump,
+    )]
+    pub participation: Account<'info, Participation>,
+
+    #[account(
+        seeds = [b"reputation", kol.key().as_ref()],
+        bump,
+    )]
+    pub kol_reputation: Option<Account<'info, KolReputation>>,
+
+    pub eligibility_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(kol: Pubkey)]
+pub struct SetKolReputation<'info> {
+    #[account(
+        seeds = [b"marketplace"],
+        bump,
+    )]
+    pub marketplace_state: Account<'info, MarketplaceState>,
+
+    #[account(
+        mut,
+        constraint = oracle.key() == marketplace_state.oracle @ CustomErrorCode::Unauthorized
+    )]
+    pub oracle: Signer<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = oracle,
+        space = KolReputation::INIT_SPACE,
+        seeds = [b"reputation", kol.as_ref()],
+        bump,
+    )]
+    pub kol_reputation: Account<'info, KolReputation>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct LeaveOpenCampaign<'info> {
+    #[account(mut)]
+    pub kol: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"open_campaign", open_campaign.creator_address.as_ref(), &open_campaign.counter.to_le_bytes()],
+        bump,
+    )]
+    pub open_campaign: Account<'info, OpenCampaign>,
+
+    #[account(
+        mut,
+        seeds = [b"participation", open_campaign.key().as_ref(), kol.key().as_ref()],
+        bump,
+        close = kol,
+    )]
+    pub participation: Account<'info, Participation>,
+}
+
+#[derive(Accounts)]
+pub struct CloseParticipation<'info> {
+    #[account(mut)]
+    pub kol: Signer<'info>,
+
+    /// CHECK: May already be closed, only deserialized in the handler while it still exists
+    #[account(address = participation.open_campaign)]
+    pub open_campaign: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"participation", open_campaign.key().as_ref(), kol.key().as_ref()],
+        bump,
+        close = kol,
+    )]
+    pub participation: Account<'info, Participation>,
+}
+
+#[derive(Accounts)]
+pub struct SetOpenCampaignAllocation<'info> {
+    #[account(
+        seeds = [b"marketplace"],
+        bump,
+    )]
+    pub marketplace_state: Account<'info, MarketplaceState>,
+
+    #[account(
+        mut,
+        constraint = owner.key() == marketplace_state.owner @ CustomErrorCode::Unauthorized
+    )]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"open_campaign", open_campaign.creator_address.as_ref(), &open_campaign.counter.to_le_bytes()],
+        bump,
+    )]
+    pub open_campaign: Account<'info, OpenCampaign>,
+
+    #[account(
+        mut,
+        seeds = [b"participation", open_campaign.key().as_ref(), participation.kol.as_ref()],
+        bump,
+    )]
+    pub participation: Account<'info, Participation>,
+}
+
+#[derive(Accounts)]
+pub struct SubmitOpenCampaignEntry<'info> {
+    pub kol: Signer<'info>,
+
+    #[account(
+        seeds = [b"open_campaign", open_campaign.creator_address.as_ref(), &open_campaign.counter.to_le_bytes()],
+        bump,
+    )]
+    pub open_campaign: Account<'info, OpenCampaign>,
+
+    #[account(
+        mut,
+        seeds = [b"participation", open_campaign.key().as_ref(), kol.key().as_ref()],
+        bump,
+    )]
+    pub participation: Account<'info, Participation>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptOpenCampaignSubmission<'info> {
+    #[account(
+        seeds = [b"marketplace"],
+        bump,
+    )]
+    pub marketplace_state: Account<'info, MarketplaceState>,
+
+    #[account(
+        constraint = owner.key() == marketplace_state.owner @ CustomErrorCode::Unauthorized
+    )]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"open_campaign", open_campaign.creator_address.as_ref(), &open_campaign.counter.to_le_bytes()],
+        bump,
+    )]
+    pub open_campaign: Account<'info, OpenCampaign>,
+
+    #[account(
+        mut,
+        seeds = [b"participation", open_campaign.key().as_ref(), participation.kol.as_ref()],
+        bump,
+    )]
+    pub participation: Account<'info, Participation>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimOpenCampaignAllocation<'info> {
+    #[account(
+        seeds = [b"marketplace"],
+        bump,
+    )]
+    pub marketplace_state: Account<'info, MarketplaceState>,
+
+    #[account(mut)]
+    pub kol: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"open_campaign", open_campaign.creator_address.as_ref(), &open_campaign.counter.to_le_bytes()],
+        bump,
+    )]
+    pub open_campaign: Account<'info, OpenCampaign>,
+
+    #[account(
+        init_if_needed,
+        payer = kol,
+        space = CreatorProfile::INIT_SPACE,
+        seeds = [b"creator_profile", open_campaign.creator_address.as_ref()],
+        bump,
+    )]
+    pub creator_profile: Account<'info, CreatorProfile>,
+
+    #[account(
+        mut,
+        seeds = [b"participation", open_campaign.key().as_ref(), kol.key().as_ref()],
+        bump,
+    )]
+    pub participation: Account<'info, Participation>,
+
+    #[account(
+        mut,
+        constraint = campaign_token_account.owner == open_campaign.key(),
+        constraint = campaign_token_account.mint == open_campaign.token_mint
+    )]
+    pub campaign_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = kol_token_account.owner == kol.key(),
+        constraint = kol_token_account.mint == open_campaign.token_mint
+    )]
+    pub kol_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = owner_token_account.owner == marketplace_state.owner,
+        constraint = owner_token_account.mint == open_campaign.token_mint
+    )]
+    pub owner_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        constraint = token_mint.key() == open_campaign.token_mint
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RecordEngagementScore<'info> {
+    #[account(
+        seeds = [b"marketplace"],
+        bump,
+    )]
+    pub marketplace_state: Account<'info, MarketplaceState>,
+
+    #[account(
+        constraint = oracle.key() == marketplace_state.oracle @ CustomErrorCode::Unauthorized
+    )]
+    pub oracle: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"open_campaign", open_campaign.creator_address.as_ref(), &open_campaign.counter.to_le_bytes()],
+        bump,
+    )]
+    pub open_campaign: Account<'info, OpenCampaign>,
+
+    #[account(
+        mut,
+        seeds = [b"participation", open_campaign.key().as_ref(), participation.kol.as_ref()],
+        bump,
+    )]
+    pub participation: Account<'info, Participation>,
+}
+
+#[derive(Accounts)]
+pub struct SweepOpenCampaignDust<'info> {
+    #[account(
+        seeds = [b"marketplace"],
+        bump,
+    )]
+    pub marketplace_state: Account<'info, MarketplaceState>,
+
+    pub caller: Signer<'info>,
+
+    #[account(
+        seeds = [b"open_campaign", open_campaign.creator_address.as_ref(), &open_campaign.counter.to_le_bytes()],
+        bump,
+    )]
+    pub open_campaign: Account<'info, OpenCampaign>,
+
+    #[account(
+        mut,
+        constraint = campaign_token_account.owner == open_campaign.key(),
+        constraint = campaign_token_account.mint == open_campaign.token_mint
+    )]
+    pub campaign_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = owner_token_account.owner == marketplace_state.owner,
+        constraint = owner_token_account.mint == open_campaign.token_mint
+    )]
+    pub owner_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = creator_token_account.owner == open_campaign.creator_address,
+        constraint = creator_token_account.mint == open_campaign.token_mint
+    )]
+    pub creator_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        constraint = token_mint.key() == open_campaign.token_mint
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct ReclaimUnclaimedOpenCampaignRewards<'info> {
+    #[account(
+        seeds = [b"marketplace"],
+        bump,
+    )]
+    pub marketplace_state: Account<'info, MarketplaceState>,
+
+    pub creator: Signer<'info>,
+
+    #[account(
+        seeds = [b"open_campaign", open_campaign.creator_address.as_ref(), &open_campaign.counter.to_le_bytes()],
+        bump,
+        constraint = open_campaign.creator_address == creator.key() @ CustomErrorCode::Unauthorized
+    )]
+    pub open_campaign: Account<'info, OpenCampaign>,
+
+    #[account(
+        mut,
+        constraint = campaign_token_account.owner == open_campaign.key(),
+        constraint = campaign_token_account.mint == open_campaign.token_mint
+    )]
+    pub campaign_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = creator_token_account.owner == open_campaign.creator_address,
+        constraint = creator_token_account.mint == open_campaign.token_mint
+    )]
+    pub creator_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        constraint = token_mint.key() == open_campaign.token_mint
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+#[instruction(leaf_count: u32)]
+pub struct InitializeClaimBitmap<'info> {
+    #[account(
+        seeds = [b"marketplace"],
+        bump,
+    )]
+    pub marketplace_state: Account<'info, MarketplaceState>,
+
+    #[account(
+        mut,
+        constraint = owner.key() == marketplace_state.owner @ CustomErrorCode::Unauthorized
+    )]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"open_campaign", open_campaign.creator_address.as_ref(), &open_campaign.counter.to_le_bytes()],
+        bump,
+    )]
+    pub open_campaign: Account<'info, OpenCampaign>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = ClaimBitmap::space(leaf_count),
+        seeds = [b"claim_bitmap", open_campaign.key().as_ref()],
+        bump,
+    )]
+    pub claim_bitmap: Account<'info, ClaimBitmap>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimOpenReward<'info> {
+    #[account(
+        seeds = [b"marketplace"],
+        bump,
+    )]
+    pub marketplace_state: Account<'info, MarketplaceState>,
+
+    #[account(mut)]
+    pub kol: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"open_campaign", open_campaign.creator_address.as_ref(), &open_campaign.counter.to_le_bytes()],
+        bump,
+    )]
+    pub open_campaign: Account<'info, OpenCampaign>,
+
+    #[account(
+        init_if_needed,
+        payer = kol,
+        space = CreatorProfile::INIT_SPACE,
+        seeds = [b"creator_profile", open_campaign.creator_address.as_ref()],
+        bump,
+    )]
+    pub creator_profile: Account<'info, CreatorProfile>,
+
+    #[account(
+        mut,
+        seeds = [b"claim_bitmap", open_campaign.key().as_ref()],
+        bump,
+    )]
+    pub claim_bitmap: Account<'info, ClaimBitmap>,
+
+    #[account(
+        mut,
+        constraint = campaign_token_account.owner == open_campaign.key(),
+        constraint = campaign_token_account.mint == open_campaign.token_mint
+    )]
+    pub campaign_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = kol_token_account.owner == kol.key(),
+        constraint = kol_token_account.mint == open_campaign.token_mint
+    )]
+    pub kol_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = owner_token_account.owner == marketplace_state.owner,
+        constraint = owner_token_account.mint == open_campaign.token_mint
+    )]
+    pub owner_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        constraint = token_mint.key() == open_campaign.token_mint
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CompleteOpenCampaign<'info> {
+    #[account(
+        mut,
+        seeds = [b"marketplace"],
+        bump,
+    )]
+    pub marketplace_state: Account<'info, MarketplaceState>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"open_campaign", open_campaign.creator_address.as_ref(), &open_campaign.counter.to_le_bytes()],
+        bump,
+    )]
+    pub open_campaign: Account<'info, OpenCampaign>,
+
+    #[account(
+        mut,
+        constraint = campaign_token_account.owner == open_campaign.key(),
+        constraint = campaign_token_account.mint == open_campaign.token_mint
+    )]
+    pub campaign_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = owner_token_account.owner == marketplace_state.owner,
+        constraint = owner_token_account.mint == open_campaign.token_mint
+    )]
+    pub owner_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = creator_token_account.owner == open_campaign.creator_address,
+        constraint = creator_token_account.mint == open_campaign.token_mint
+    )]
+    pub creator_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        constraint = token_mint.key() == open_campaign.token_mint
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct CloseCampaign<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"campaign", campaign.creator_address.as_ref(), &campaign.counter.to_le_bytes()],
+        bump,
+        close = creator,
+        constraint = campaign.creator_address == creator.key() @ CustomErrorCode::Unauthorized,
+        constraint = !campaign.is_native @ CustomErrorCode::EscrowAssetMismatch
+    )]
+    pub campaign: Account<'info, Campaign>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = campaign,
+        associated_token::token_program = token_program
+    )]
+    pub campaign_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    // Required when the campaign was resolved by the arbiter
+    #[account(
+        mut,
+        seeds = [b"dispute", campaign.key().as_ref()],
+        bump,
+    )]
+    pub dispute: Option<Account<'info, Dispute>>,
+
+    /// CHECK: Receives the dispute rent, checked against `dispute.opened_by`
+    #[account(mut)]
+    pub dispute_opener: Option<UncheckedAccount<'info>>,
+
+    // Required when a vesting schedule was set on a campaign that never paid out
+    #[account(
+        mut,
+        seeds = [b"vesting", campaign.key().as_ref()],
+        bump,
+    )]
+    pub vesting: Option<Account<'info, Vesting>>,
+
+    #[account(mut)]
+    pub vesting_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    // Writable so withheld transfer fees can be harvested into it
+    #[account(
+        mut,
+        constraint = token_mint.key() == campaign.token_mint
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct CancelOpenCampaign<'info> {
+    #[account(
+        seeds = [b"marketplace"],
+        bump,
+    )]
+    pub marketplace_state: Account<'info, MarketplaceState>,
+
+    pub creator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"open_campaign", open_campaign.creator_address.as_ref(), &open_campaign.counter.to_le_bytes()],
+        bump,
+        constraint = open_campaign.creator_address == creator.key() @ CustomErrorCode::Unauthorized
+    )]
+    pub open_campaign: Account<'info, OpenCampaign>,
+
+    #[account(
+        mut,
+        constraint = campaign_token_account.owner == open_campaign.key(),
+        constraint = campaign_token_account.mint == open_campaign.token_mint
+    )]
+    pub campaign_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = creator_token_account.owner == creator.key(),
+        constraint = creator_token_account.mint == open_campaign.token_mint
+    )]
+    pub creator_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        constraint = token_mint.key() == open_campaign.token_mint
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct ExpireOpenCampaign<'info> {
+    #[account(
+        seeds = [b"marketplace"],
+        bump,
+    )]
+    pub marketplace_state: Account<'info, MarketplaceState>,
+
+    pub caller: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"open_campaign", open_campaign.creator_address.as_ref(), &open_campaign.counter.to_le_bytes()],
+        bump,
+    )]
+    pub open_campaign: Account<'info, OpenCampaign>,
+
+    #[account(
+        mut,
+        constraint = campaign_token_account.owner == open_campaign.key(),
+        constraint = campaign_token_account.mint == open_campaign.token_mint
+    )]
+    pub campaign_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = creator_token_account.owner == open_campaign.creator_address,
+        constraint = creator_token_account.mint == open_campaign.token_mint
+    )]
+    pub creator_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        constraint = token_mint.key() == open_campaign.token_mint
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct CloseOpenCampaign<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"open_campaign", open_campaign.creator_address.as_ref(), &open_campaign.counter.to_le_bytes()],
+        bump,
+        close = creator,
+        constraint = open_campaign.creator_address == creator.key() @ CustomErrorCode::Unauthorized
+    )]
+    pub open_campaign: Account<'info, OpenCampaign>,
 
-        // Find the index of the token to remove
-        if let Some(index) = marketplace_state
-            .allowed_tokens
-            .iter()
-            .position(|x| x == &token_mint)
-        {
-            // Remove the token and its corresponding decimal
-            marketplace_state.allowed_tokens.remove(index);
-            marketplace_state.token_decimals.remove(index);
+    // Only exists for campaigns settled by Merkle root
+    #[account(
+        mut,
+        seeds = [b"claim_bitmap", open_campaign.key().as_ref()],
+        bump,
+        close = creator
+    )]
+    pub claim_bitmap: Option<Account<'info, ClaimBitmap>>,
 
-            msg!("Removed token: {}", token_mint);
-            Ok(())
-        } else {
-            err!(CustomErrorCode::TokenNotFound)
-        }
-    }
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = open_campaign,
+        associated_token::token_program = token_program
+    )]
+    pub campaign_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    // Writable so withheld transfer fees can be harvested into it
+    #[account(
+        mut,
+        constraint = token_mint.key() == open_campaign.token_mint
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
 }
 
 #[derive(Accounts)]
-pub struct InitializeMarketplace<'info> {
-    #[account(mut)]
-    pub owner: Signer<'info>,
+pub struct UpdateAllowedTokens<'info> {
     #[account(
-        init,
-        payer = owner,
-        space = MarketplaceState::INIT_SPACE,
+        mut,
         seeds = [b"marketplace"],
         bump,
+        constraint = marketplace_state.owner == owner.key() @ CustomErrorCode::Unauthorized
     )]
     pub marketplace_state: Account<'info, MarketplaceState>,
-    pub system_program: Program<'info, System>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
 }
 
 #[derive(Accounts)]
-pub struct CreateNewCampaign<'info> {
+pub struct CreateSubscriptionCampaign<'info> {
     #[account(
         mut,
         seeds = [b"marketplace"],
@@ -589,212 +7157,279 @@ pub struct CreateNewCampaign<'info> {
     #[account(
         constraint = marketplace_state.allowed_tokens.contains(&token_mint.key())
     )]
-    pub token_mint: Account<'info, Mint>,
+    pub token_mint: InterfaceAccount<'info, Mint>,
     #[account(
         init,
         payer = creator,
-        space = Campaign::INIT_SPACE,
-        seeds = [b"campaign", creator.key().as_ref(), &marketplace_state.campaign_counter.to_le_bytes()],
+        space = Subscription::INIT_SPACE,
+        seeds = [b"subscription", creator.key().as_ref(), &marketplace_state.campaign_counter.to_le_bytes()],
         bump,
     )]
-    pub campaign: Account<'info, Campaign>,
+    pub subscription: Account<'info, Subscription>,
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct UpdateCampaign<'info> {
+pub struct AcceptSubscriptionCampaign<'info> {
+    pub kol: Signer<'info>,
+
     #[account(
         mut,
-        seeds = [b"marketplace"],
+        seeds = [b"subscription", subscription.creator_address.as_ref(), &subscription.counter.to_le_bytes()],
         bump,
+        constraint = subscription.selected_kol == kol.key() @ CustomErrorCode::Unauthorized
     )]
-    pub marketplace_state: Account<'info, MarketplaceState>,
-    #[account(mut)]
-    pub creator: Signer<'info>,
+    pub subscription: Account<'info, Subscription>,
+
     #[account(
-        mut,
-        seeds = [b"campaign", creator.key().as_ref(), &campaign.counter.to_le_bytes()],
-        bump,
-        constraint = campaign.creator_address == creator.key() @ CustomErrorCode::Unauthorized
+        associated_token::mint = token_mint,
+        associated_token::authority = subscription,
+        associated_token::token_program = token_program
     )]
-    pub campaign: Account<'info, Campaign>,
+    pub subscription_token_account: InterfaceAccount<'info, TokenAccount>,
 
-    #[account(mut,
-        constraint = token_mint.key() == campaign.token_mint
+    #[account(
+        constraint = token_mint.key() == subscription.token_mint
     )]
-    pub token_mint: Account<'info, Mint>,
-    pub token_program: Program<'info, anchor_spl::token::Token>,
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
 }
 
 #[derive(Accounts)]
-pub struct AcceptProjectCampaign<'info> {
+pub struct SubmitSubscriptionDeliverable<'info> {
     #[account(
-        mut,
         seeds = [b"marketplace"],
         bump,
     )]
     pub marketplace_state: Account<'info, MarketplaceState>,
-    #[account(mut)]
+
     pub kol: Signer<'info>,
+
     #[account(
         mut,
-        seeds = [b"campaign", campaign.creator_address.as_ref(), &campaign.counter.to_le_bytes()],
+        seeds = [b"subscription", subscription.creator_address.as_ref(), &subscription.counter.to_le_bytes()],
         bump,
+        constraint = subscription.selected_kol == kol.key() @ CustomErrorCode::Unauthorized
     )]
-    pub campaign: Account<'info, Campaign>,
+    pub subscription: Account<'info, Subscription>,
 }
 
 #[derive(Accounts)]
-pub struct DiscardProjectCampaign<'info> {
+pub struct FulfilSubscriptionPeriod<'info> {
     #[account(
-        mut,
         seeds = [b"marketplace"],
         bump,
     )]
     pub marketplace_state: Account<'info, MarketplaceState>,
 
     #[account(mut)]
-    pub creator: Signer<'info>,
+    pub caller: Signer<'info>,
 
     #[account(
         mut,
-        seeds = [b"campaign", campaign.creator_address.as_ref(), &campaign.counter.to_le_bytes()],
+        seeds = [b"subscription", subscription.creator_address.as_ref(), &subscription.counter.to_le_bytes()],
         bump,
     )]
-    pub campaign: Account<'info, Campaign>,
+    pub subscription: Account<'info, Subscription>,
+
+    #[account(
+        init_if_needed,
+        payer = caller,
+        space = CreatorProfile::INIT_SPACE,
+        seeds = [b"creator_profile", subscription.creator_address.as_ref()],
+        bump,
+    )]
+    pub creator_profile: Account<'info, CreatorProfile>,
 
     #[account(
         mut,
-        constraint = campaign_token_account.owner == campaign.key(),
-        constraint = marketplace_state.allowed_tokens.contains(&campaign_token_account.mint)
+        associated_token::mint = token_mint,
+        associated_token::authority = subscription,
+        associated_token::token_program = token_program
     )]
-    pub campaign_token_account: Account<'info, TokenAccount>,
+    pub subscription_token_account: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         mut,
-        constraint = creator_token_account.owner == campaign.creator_address,
-        constraint = marketplace_state.allowed_tokens.contains(&creator_token_account.mint)
+        constraint = kol_token_account.owner == subscription.selected_kol,
+        constraint = kol_token_account.mint == subscription.token_mint
     )]
-    pub creator_token_account: Account<'info, TokenAccount>,
+    pub kol_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub referrer_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub agency_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         mut,
-        constraint = token_mint.key() == campaign.token_mint
+        constraint = owner_token_account.owner == marketplace_state.owner,
+        constraint = owner_token_account.mint == subscription.token_mint
+    )]
+    pub owner_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = creator_token_account.owner == subscription.creator_address,
+        constraint = creator_token_account.mint == subscription.token_mint
+    )]
+    pub creator_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        constraint = token_mint.key() == subscription.token_mint
     )]
-    pub token_mint: Account<'info, Mint>,
-    pub token_program: Program<'info, anchor_spl::token::Token>,
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct FulfilProjectCampaign<'info> {
+pub struct TerminateSubscriptionCampaign<'info> {
     #[account(
-        mut,
         seeds = [b"marketplace"],
         bump,
     )]
     pub marketplace_state: Account<'info, MarketplaceState>,
 
-    #[account(mut)]
-    pub owner: Signer<'info>,
+    pub party: Signer<'info>,
 
     #[account(
         mut,
-        seeds = [b"campaign", campaign.creator_address.as_ref(), &campaign.counter.to_le_bytes()],
+        seeds = [b"subscription", subscription.creator_address.as_ref(), &subscription.counter.to_le_bytes()],
         bump,
     )]
-    pub campaign: Account<'info, Campaign>,
+    pub subscription: Account<'info, Subscription>,
 
-    #[account(mut,
-        constraint = campaign_token_account.owner == campaign.key(),
-        constraint = marketplace_state.allowed_tokens.contains(&campaign_token_account.mint)
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = subscription,
+        associated_token::token_program = token_program
     )]
-    pub campaign_token_account: Account<'info, TokenAccount>,
+    pub subscription_token_account: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         mut,
-        constraint = kol_token_account.owner == campaign.selected_kol,
-        constraint = marketplace_state.allowed_tokens.contains(&kol_token_account.mint)
+        constraint = creator_token_account.owner == subscription.creator_address,
+        constraint = creator_token_account.mint == subscription.token_mint
+    )]
+    pub creator_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        constraint = token_mint.key() == subscription.token_mint
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct ExpireSubscriptionCampaign<'info> {
+    pub caller: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"subscription", subscription.creator_address.as_ref(), &subscription.counter.to_le_bytes()],
+        bump,
     )]
-    pub kol_token_account: Account<'info, TokenAccount>,
+    pub subscription: Account<'info, Subscription>,
 
     #[account(
         mut,
-        constraint = owner_token_account.owner == marketplace_state.owner,
-        constraint = marketplace_state.allowed_tokens.contains(&owner_token_account.mint)
+        associated_token::mint = token_mint,
+        associated_token::authority = subscription,
+        associated_token::token_program = token_program
     )]
-    pub owner_token_account: Account<'info, TokenAccount>,
+    pub subscription_token_account: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         mut,
-        constraint = token_mint.key() == campaign.token_mint
+        constraint = creator_token_account.owner == subscription.creator_address,
+        constraint = creator_token_account.mint == subscription.token_mint
     )]
-    pub token_mint: Account<'info, Mint>,
-    pub token_program: Program<'info, anchor_spl::token::Token>,
+    pub creator_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        constraint = token_mint.key() == subscription.token_mint
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
 }
 
 #[derive(Accounts)]
-pub struct CreateOpenCampaign<'info> {
+pub struct SetSubscriptionReferrer<'info> {
     #[account(
-        mut,
         seeds = [b"marketplace"],
         bump,
     )]
     pub marketplace_state: Account<'info, MarketplaceState>,
-    #[account(mut)]
-    pub creator: Signer<'info>,
+
+    // Referrers are paid out of the platform fee, so only the owner approves them
     #[account(
-        constraint = marketplace_state.allowed_tokens.contains(&token_mint.key())
+        constraint = owner.key() == marketplace_state.owner @ CustomErrorCode::Unauthorized
     )]
-    pub token_mint: Account<'info, Mint>,
+    pub owner: Signer<'info>,
+
     #[account(
-        init,
-        payer = creator,
-        space = OpenCampaign::INIT_SPACE,
-        seeds = [b"open_campaign", creator.key().as_ref(), &marketplace_state.campaign_counter.to_le_bytes()],
+        mut,
+        seeds = [b"subscription", subscription.creator_address.as_ref(), &subscription.counter.to_le_bytes()],
         bump,
     )]
-    pub open_campaign: Account<'info, OpenCampaign>,
-    pub system_program: Program<'info, System>,
+    pub subscription: Account<'info, Subscription>,
 }
 
 #[derive(Accounts)]
-pub struct CompleteOpenCampaign<'info> {
+pub struct SetSubscriptionAgency<'info> {
     #[account(
-        mut,
         seeds = [b"marketplace"],
         bump,
     )]
     pub marketplace_state: Account<'info, MarketplaceState>,
 
-    #[account(mut)]
-    pub owner: Signer<'info>,
+    pub kol: Signer<'info>,
 
     #[account(
         mut,
-        seeds = [b"open_campaign", open_campaign.creator_address.as_ref(), &open_campaign.counter.to_le_bytes()],
+        seeds = [b"subscription", subscription.creator_address.as_ref(), &subscription.counter.to_le_bytes()],
         bump,
+        constraint = subscription.selected_kol == kol.key() @ CustomErrorCode::Unauthorized
     )]
-    pub open_campaign: Account<'info, OpenCampaign>,
+    pub subscription: Account<'info, Subscription>,
+}
+
+#[derive(Accounts)]
+pub struct CloseSubscriptionCampaign<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
 
     #[account(
         mut,
-        constraint = campaign_token_account.owner == open_campaign.key(),
-        constraint = marketplace_state.allowed_tokens.contains(&campaign_token_account.mint)
+        seeds = [b"subscription", subscription.creator_address.as_ref(), &subscription.counter.to_le_bytes()],
+        bump,
+        close = creator,
+        constraint = subscription.creator_address == creator.key() @ CustomErrorCode::Unauthorized
     )]
-    pub campaign_token_account: Account<'info, TokenAccount>,
+    pub subscription: Account<'info, Subscription>,
 
     #[account(
         mut,
-        constraint = owner_token_account.owner == marketplace_state.owner,
-        constraint = marketplace_state.allowed_tokens.contains(&owner_token_account.mint)
+        associated_token::mint = token_mint,
+        associated_token::authority = subscription,
+        associated_token::token_program = token_program
     )]
-    pub owner_token_account: Account<'info, TokenAccount>,
+    pub subscription_token_account: InterfaceAccount<'info, TokenAccount>,
 
-    pub token_program: Program<'info, anchor_spl::token::Token>,
+    // Writable so withheld transfer fees can be harvested into it
+    #[account(
+        mut,
+        constraint = token_mint.key() == subscription.token_mint
+    )]
+    pub token_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
 }
 
 #[derive(Accounts)]
-pub struct UpdateAllowedTokens<'info> {
+pub struct UpdateMarketplaceSettings<'info> {
     #[account(
         mut,
         seeds = [b"marketplace"],
@@ -807,6 +7442,54 @@ pub struct UpdateAllowedTokens<'info> {
     pub owner: Signer<'info>,
 }
 
+#[derive(Accounts)]
+#[instruction(token_mint: Pubkey)]
+pub struct SetPriceFeed<'info> {
+    #[account(
+        seeds = [b"marketplace"],
+        bump,
+        constraint = marketplace_state.owner == owner.key() @ CustomErrorCode::Unauthorized
+    )]
+    pub marketplace_state: Account<'info, MarketplaceState>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = PriceFeedConfig::INIT_SPACE,
+        seeds = [b"price_feed", token_mint.as_ref()],
+        bump,
+    )]
+    pub price_feed_config: Account<'info, PriceFeedConfig>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(creator: Pubkey)]
+pub struct SetCreatorFeeOverride<'info> {
+    #[account(
+        seeds = [b"marketplace"],
+        bump,
+        constraint = marketplace_state.owner == owner.key() @ CustomErrorCode::Unauthorized
+    )]
+    pub marketplace_state: Account<'info, MarketplaceState>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = CreatorProfile::INIT_SPACE,
+        seeds = [b"creator_profile", creator.as_ref()],
+        bump,
+    )]
+    pub creator_profile: Account<'info, CreatorProfile>,
+    pub system_program: Program<'info, System>,
+}
+
 #[event]
 pub struct CampaignUpdated {
     pub campaign_id: [u8; 4],
@@ -823,3 +7506,149 @@ pub struct CampaignAccepted {
 pub struct CampaignFulfilled {
     pub campaign_id: [u8; 4],
 }
+
+#[event]
+pub struct DeliverableSubmitted {
+    pub campaign_id: [u8; 4],
+    pub submitted_by: Pubkey,
+    pub content_hash: [u8; 32],
+    pub submitted_at: i64,
+}
+
+#[event]
+pub struct DisputeOpened {
+    pub campaign_id: [u8; 4],
+    pub opened_by: Pubkey,
+    pub evidence_hash: [u8; 32],
+}
+
+#[event]
+pub struct DisputeResolved {
+    pub campaign_id: [u8; 4],
+    pub kol_amount: u64,
+    pub creator_amount: u64,
+}
+
+#[event]
+pub struct CampaignClosed {
+    pub campaign_id: [u8; 4],
+    pub creator: Pubkey,
+    pub selected_kol: Pubkey,
+    pub token_mint: Pubkey,
+    pub amount_offered: u64,
+    pub campaign_status: CampaignStatus,
+    pub closed_at: i64,
+}
+
+#[event]
+pub struct OpenCampaignCancelled {
+    pub campaign_id: [u8; 4],
+    pub creator: Pubkey,
+    pub token_mint: Pubkey,
+    pub refund_amount: u64,
+    pub participant_count: u32,
+    pub cancelled_at: i64,
+}
+
+#[event]
+pub struct OpenCampaignExpired {
+    pub campaign_id: [u8; 4],
+    pub creator: Pubkey,
+    pub campaign_status: OpenCampaignStatus,
+    pub refund_amount: u64,
+    pub expired_by: Pubkey,
+    pub expired_at: i64,
+}
+
+#[event]
+pub struct OpenCampaignRewardsReclaimed {
+    pub campaign_id: [u8; 4],
+    pub creator: Pubkey,
+    pub refund_amount: u64,
+    pub reclaimed_at: i64,
+}
+
+#[event]
+pub struct UsdPriceSettled {
+    pub campaign_id: [u8; 4],
+    pub usd_value: u64,
+    pub amount_offered: u64,
+    pub top_up_amount: u64,
+    pub refund_amount: u64,
+    pub settled_at: i64,
+}
+
+#[event]
+pub struct VestedTokensClaimed {
+    pub campaign: Pubkey,
+    pub kol: Pubkey,
+    pub amount: u64,
+    pub claimed_amount: u64,
+    pub total_amount: u64,
+}
+
+#[event]
+pub struct CampaignUnfulfilled {
+    pub campaign_id: [u8; 4],
+    pub selected_kol: Pubkey,
+    pub refund_amount: u64,
+    pub marked_by: Pubkey,
+    pub marked_at: i64,
+}
+
+#[event]
+pub struct BondSettled {
+    pub campaign_id: [u8; 4],
+    pub kol: Pubkey,
+    pub returned_amount: u64,
+    pub slashed_to_creator: u64,
+    pub slashed_to_treasury: u64,
+}
+
+#[event]
+pub struct SubscriptionPeriodPaid {
+    pub subscription_id: [u8; 4],
+    pub period: u32,
+    pub kol_amount: u64,
+    pub owner_amount: u64,
+    pub refund_amount: u64,
+}
+
+#[event]
+pub struct SubscriptionClosed {
+    pub subscription_id: [u8; 4],
+    pub creator: Pubkey,
+    pub selected_kol: Pubkey,
+    pub periods_paid: u32,
+    pub status: SubscriptionStatus,
+    pub closed_at: i64,
+}
+
+#[event]
+pub struct SubscriptionTerminated {
+    pub subscription_id: [u8; 4],
+    pub terminated_by: Pubkey,
+    pub periods_paid: u32,
+    pub unused_periods: u32,
+    pub refund_amount: u64,
+}
+
+#[event]
+pub struct SubscriptionExpired {
+    pub subscription_id: [u8; 4],
+    pub periods_paid: u32,
+    pub unused_periods: u32,
+    pub refund_amount: u64,
+    pub expired_by: Pubkey,
+    pub expired_at: i64,
+}
+
+#[event]
+pub struct OpenCampaignClosed {
+    pub campaign_id: [u8; 4],
+    pub creator: Pubkey,
+    pub token_mint: Pubkey,
+    pub pool_amount: u64,
+    pub campaign_status: OpenCampaignStatus,
+    pub closed_at: i64,
+}
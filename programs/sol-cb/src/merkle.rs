@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+
+// Domain separation so a leaf can never be passed off as an inner node
+pub const LEAF_PREFIX: &[u8] = &[0];
+pub const NODE_PREFIX: &[u8] = &[1];
+
+/// Hash of a (index, kol, amount) payout leaf.
+pub fn leaf_hash(index: u32, kol: &Pubkey, amount: u64) -> [u8; 32] {
+    hashv(&[
+        LEAF_PREFIX,
+        &index.to_le_bytes(),
+        kol.as_ref(),
+        &amount.to_le_bytes(),
+    ])
+    .to_bytes()
+}
+
+/// Hash of an allowlist leaf.
+pub fn allowlist_leaf_hash(kol: &Pubkey) -> [u8; 32] {
+    hashv(&[LEAF_PREFIX, kol.as_ref()]).to_bytes()
+}
+
+/// Hash of two sibling nodes. Pairs are sorted so proofs don't need to carry
+/// left/right positions.
+pub fn hash_pair(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    if a <= b {
+        hashv(&[NODE_PREFIX, a, b]).to_bytes()
+    } else {
+        hashv(&[NODE_PREFIX, b, a]).to_bytes()
+    }
+}
+
+/// Walks `proof` from `leaf` up to the root and compares it with `root`.
+pub fn verify_proof(proof: &[[u8; 32]], root: &[u8; 32], leaf: [u8; 32]) -> bool {
+    let computed = proof
+        .iter()
+        .fold(leaf, |node, sibling| hash_pair(&node, sibling));
+    computed == *root
+}
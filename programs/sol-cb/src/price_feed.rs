@@ -0,0 +1,104 @@
+use anchor_lang::prelude::*;
+
+use crate::sol_cb::{CustomErrorCode, DIVIDER};
+
+// Offsets into a Pyth (v2) price account. Only the fields needed to value a
+// campaign are read, so a local account with the same layout can stand in
+// for a real feed in tests.
+pub const MAGIC: u32 = 0xa1b2_c3d4;
+pub const PRICE_ACCOUNT_TYPE: u32 = 3;
+pub const STATUS_TRADING: u32 = 1;
+const MAGIC_OFFSET: usize = 0;
+const ACCOUNT_TYPE_OFFSET: usize = 8;
+const EXPO_OFFSET: usize = 20;
+const TIMESTAMP_OFFSET: usize = 96;
+const AGG_PRICE_OFFSET: usize = 208;
+const AGG_CONF_OFFSET: usize = 216;
+const AGG_STATUS_OFFSET: usize = 224;
+const MIN_ACCOUNT_LEN: usize = 240;
+
+// Campaign USD values carry 6 decimals, e.g. 1_500_000 is $1.50
+pub const USD_DECIMALS: u32 = 6;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PriceFeed {
+    pub price: i64,
+    pub conf: u64,
+    pub expo: i32,
+    pub publish_time: i64,
+}
+
+impl PriceFeed {
+    /// Reads the aggregate price out of a Pyth-layout price account.
+    pub fn load(price_feed: &AccountInfo) -> Result<Self> {
+        let data = price_feed.try_borrow_data()?;
+        if data.len() < MIN_ACCOUNT_LEN
+            || read_u32(&data, MAGIC_OFFSET) != MAGIC
+            || read_u32(&data, ACCOUNT_TYPE_OFFSET) != PRICE_ACCOUNT_TYPE
+        {
+            return err!(CustomErrorCode::InvalidPriceFeed);
+        }
+
+        if read_u32(&data, AGG_STATUS_OFFSET) != STATUS_TRADING {
+            return err!(CustomErrorCode::InvalidPriceFeed);
+        }
+
+        let price = read_i64(&data, AGG_PRICE_OFFSET);
+        if price <= 0 {
+            return err!(CustomErrorCode::InvalidPriceFeed);
+        }
+
+        Ok(Self {
+            price,
+            conf: read_u64(&data, AGG_CONF_OFFSET),
+            expo: read_u32(&data, EXPO_OFFSET) as i32,
+            publish_time: read_i64(&data, TIMESTAMP_OFFSET),
+        })
+    }
+
+    /// Rejects prices older than `max_staleness` seconds or whose confidence
+    /// interval is wider than `max_confidence_bps` of the price.
+    pub fn check(
+        &self,
+        current_time: i64,
+        max_staleness: i64,
+        max_confidence_bps: u16,
+    ) -> Result<()> {
+        if current_time.saturating_sub(self.publish_time) > max_staleness {
+            return err!(CustomErrorCode::StalePrice);
+        }
+
+        let max_conf = (self.price as u128) * (max_confidence_bps as u128) / (DIVIDER as u128);
+        if self.conf as u128 > max_conf {
+            return err!(CustomErrorCode::PriceConfidenceTooWide);
+        }
+
+        Ok(())
+    }
+
+    /// Token base units worth `usd_value` (6 decimals) at this price, rounded down.
+    pub fn token_amount_for_usd(&self, usd_value: u64, token_decimals: u8) -> Option<u64> {
+        let mut numerator =
+            (usd_value as u128).checked_mul(10u128.checked_pow(token_decimals as u32)?)?;
+        let mut denominator = (self.price as u128).checked_mul(10u128.pow(USD_DECIMALS))?;
+        if self.expo < 0 {
+            numerator = numerator.checked_mul(10u128.checked_pow(self.expo.unsigned_abs())?)?;
+        } else {
+            denominator = denominator.checked_mul(10u128.checked_pow(self.expo as u32)?)?;
+        }
+
+        u64::try_from(numerator / denominator).ok()
+    }
+}
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap())
+}
+
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+}
+
+fn read_i64(data: &[u8], offset: usize) -> i64 {
+    i64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+}